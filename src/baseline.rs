@@ -0,0 +1,300 @@
+//! Baseline of accepted violations in `.guardrails-baseline.json` at the repo root.
+//!
+//! Entries are keyed by rule id, repo-relative file and a hash of the offending
+//! line's text, so they survive line shifts. Matching is by count: a baselined
+//! line that appears once more is reported again.
+
+use crate::fingerprint::line_hash;
+use crate::rules::Violation;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+pub const BASELINE_FILE: &str = ".guardrails-baseline.json";
+
+const VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Entry {
+    pub rule: String,
+    pub file: String,
+    pub hash: String,
+    #[serde(default = "one", skip_serializing_if = "is_one")]
+    pub count: usize,
+}
+
+fn one() -> usize {
+    1
+}
+
+fn is_one(n: &usize) -> bool {
+    *n == 1
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Baseline {
+    pub version: u32,
+    pub entries: Vec<Entry>,
+}
+
+type Key = (String, String, String);
+
+/// `dir` or its first ancestor containing `.git`.
+pub fn repo_root(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+/// `path` relative to `root` with `/` separators, or None if it is outside.
+pub fn relative_path(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let parts: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    Some(parts.join("/"))
+}
+
+fn absolute(path: &str) -> PathBuf {
+    std::env::current_dir()
+        .map(|cwd| cwd.join(path))
+        .unwrap_or_else(|_| PathBuf::from(path))
+}
+
+impl Baseline {
+    pub fn load(path: &Path) -> Result<Option<Baseline>, String> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map(Some)
+                .map_err(|e| format!("invalid baseline {}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("cannot read {}: {}", path.display(), e)),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, json + "\n")
+            .map_err(|e| format!("cannot write {}: {}", path.display(), e))
+    }
+
+    /// Build from violations found in `(repo-relative file, content, violations)`.
+    pub fn from_findings<'a>(
+        findings: impl IntoIterator<Item = (&'a str, &'a str, &'a [Violation])>,
+    ) -> Baseline {
+        let mut counts: BTreeMap<Key, usize> = BTreeMap::new();
+        for (file, content, violations) in findings {
+            for v in violations {
+                let key = (v.rule.clone(), file.to_string(), line_hash(v, content));
+                *counts.entry(key).or_default() += 1;
+            }
+        }
+        Baseline {
+            version: VERSION,
+            entries: counts
+                .into_iter()
+                .map(|((rule, file, hash), count)| Entry {
+                    rule,
+                    file,
+                    hash,
+                    count,
+                })
+                .collect(),
+        }
+    }
+
+    fn counts(&self) -> HashMap<Key, usize> {
+        let mut counts = HashMap::new();
+        for e in &self.entries {
+            *counts
+                .entry((e.rule.clone(), e.file.clone(), e.hash.clone()))
+                .or_default() += e.count;
+        }
+        counts
+    }
+
+    /// Drop violations of `file` (repo-relative) that the baseline accounts for.
+    pub fn filter(&self, file: &str, content: &str, violations: Vec<Violation>) -> Vec<Violation> {
+        if !self.entries.iter().any(|e| e.file == file) {
+            return violations;
+        }
+        let mut remaining = self.counts();
+        violations
+            .into_iter()
+            .filter(|v| {
+                let key = (v.rule.clone(), file.to_string(), line_hash(v, content));
+                match remaining.get_mut(&key) {
+                    Some(n) if *n > 0 => {
+                        *n -= 1;
+                        false
+                    }
+                    _ => true,
+                }
+            })
+            .collect()
+    }
+
+    /// Keep only entries still matched by `current`, lowering counts that shrank.
+    /// Returns the number of findings removed.
+    pub fn prune(&mut self, current: &Baseline) -> usize {
+        let current = current.counts();
+        let mut removed = 0;
+        for e in &mut self.entries {
+            let now = current
+                .get(&(e.rule.clone(), e.file.clone(), e.hash.clone()))
+                .copied()
+                .unwrap_or(0);
+            if now < e.count {
+                removed += e.count - now;
+                e.count = now;
+            }
+        }
+        self.entries.retain(|e| e.count > 0);
+        removed
+    }
+}
+
+struct Cached {
+    modified: Option<SystemTime>,
+    baseline: Option<Arc<Baseline>>,
+}
+
+/// Baselines by repo root, reloaded when the file changes (the daemon is long-lived).
+#[derive(Default)]
+pub struct BaselineCache {
+    roots: Mutex<HashMap<PathBuf, Option<PathBuf>>>,
+    baselines: Mutex<HashMap<PathBuf, Cached>>,
+}
+
+impl BaselineCache {
+    fn root_for(&self, path: &Path) -> Option<PathBuf> {
+        let dir = path.parent()?.to_path_buf();
+        let mut roots = self.roots.lock().unwrap_or_else(|e| e.into_inner());
+        roots
+            .entry(dir)
+            .or_insert_with_key(|dir| repo_root(dir))
+            .clone()
+    }
+
+    fn baseline(&self, root: &Path) -> Option<Arc<Baseline>> {
+        let path = root.join(BASELINE_FILE);
+        let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+        let mut baselines = self.baselines.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(cached) = baselines.get(root) {
+            if cached.modified == modified {
+                return cached.baseline.clone();
+            }
+        }
+        let baseline = match Baseline::load(&path) {
+            Ok(b) => b.map(Arc::new),
+            Err(e) => {
+                eprintln!("guardrails: {}", e);
+                None
+            }
+        };
+        baselines.insert(
+            root.to_path_buf(),
+            Cached {
+                modified,
+                baseline: baseline.clone(),
+            },
+        );
+        baseline
+    }
+
+    /// Drop violations recorded in the baseline of `file_path`'s repository.
+    pub fn filter(
+        &self,
+        file_path: &str,
+        content: &str,
+        violations: Vec<Violation>,
+    ) -> Vec<Violation> {
+        if violations.is_empty() {
+            return violations;
+        }
+        let path = absolute(file_path);
+        let Some(root) = self.root_for(&path) else {
+            return violations;
+        };
+        let (Some(baseline), Some(file)) = (self.baseline(&root), relative_path(&root, &path))
+        else {
+            return violations;
+        };
+        baseline.filter(&file, content, violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Severity;
+
+    fn violation(rule: &str, line: u32) -> Violation {
+        Violation {
+            rule: rule.to_string(),
+            severity: Severity::High,
+            failure: String::new(),
+            file: "/repo/src/a.ts".to_string(),
+            line: Some(line),
+            column: None,
+            end_line: None,
+            end_column: None,
+        }
+    }
+
+    #[test]
+    fn filters_baselined_lines_after_drift() {
+        let before = "readFileSync(a);\n";
+        let found = [violation("sync-io", 1)];
+        let baseline = Baseline::from_findings([("src/a.ts", before, &found[..])]);
+
+        let after = "import fs from 'fs';\n\nreadFileSync(a);\nreadFileSync(b);\n";
+        let kept = baseline.filter(
+            "src/a.ts",
+            after,
+            vec![violation("sync-io", 3), violation("sync-io", 4)],
+        );
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].line, Some(4));
+    }
+
+    #[test]
+    fn duplicate_lines_are_counted() {
+        let content = "readFileSync(a);\nreadFileSync(a);\n";
+        let found = [violation("sync-io", 1)];
+        let baseline = Baseline::from_findings([("src/a.ts", content, &found[..])]);
+        let kept = baseline.filter(
+            "src/a.ts",
+            content,
+            vec![violation("sync-io", 1), violation("sync-io", 2)],
+        );
+        assert_eq!(kept.len(), 1);
+    }
+
+    #[test]
+    fn prune_drops_fixed_entries() {
+        let content = "readFileSync(a);\nel.innerHTML = x;\n";
+        let found = [violation("sync-io", 1), violation("security", 2)];
+        let mut baseline = Baseline::from_findings([("src/a.ts", content, &found[..])]);
+
+        let fixed = "readFileSync(a);\nel.textContent = x;\n";
+        let still = [violation("sync-io", 1)];
+        let current = Baseline::from_findings([("src/a.ts", fixed, &still[..])]);
+        assert_eq!(baseline.prune(&current), 1);
+        assert_eq!(baseline.entries.len(), 1);
+        assert_eq!(baseline.entries[0].rule, "sync-io");
+    }
+
+    #[test]
+    fn relative_paths_use_forward_slashes() {
+        let root = Path::new("/repo");
+        assert_eq!(
+            relative_path(root, Path::new("/repo/./src/a.ts")).as_deref(),
+            Some("src/a.ts")
+        );
+        assert!(relative_path(root, Path::new("/other/a.ts")).is_none());
+    }
+}
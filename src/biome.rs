@@ -1,5 +1,4 @@
-use crate::rules::{Severity, Violation};
-use crate::scanner::{build_line_offsets, offset_to_line};
+use crate::rules::{Severity, Span, Violation};
 use serde::Deserialize;
 use std::io::Write;
 use std::path::Path;
@@ -53,8 +52,22 @@ pub fn is_available() -> bool {
         .unwrap_or(false)
 }
 
+/// Start biome's background server so `check` can skip biome's own startup.
+pub fn start_server() -> bool {
+    Command::new("biome")
+        .arg("start")
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+pub fn stop_server() {
+    let _ = Command::new("biome").arg("stop").output();
+}
+
 /// Creates temp file in same directory as file_path to inherit project's biome.json.
-pub fn check(content: &str, file_path: &str) -> Vec<Violation> {
+/// With `use_server`, lints through the server started by `start_server`.
+pub fn check(content: &str, file_path: &str, use_server: bool) -> Vec<Violation> {
     let path = Path::new(file_path);
     let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("ts");
 
@@ -104,10 +117,12 @@ pub fn check(content: &str, file_path: &str) -> Vec<Violation> {
         }
     };
 
-    let output = match Command::new("biome")
-        .args(["lint", "--reporter=json", temp_path_str])
-        .output()
-    {
+    let mut command = Command::new("biome");
+    command.args(["lint", "--reporter=json"]);
+    if use_server {
+        command.arg("--use-server");
+    }
+    let output = match command.arg(temp_path_str).output() {
         Ok(o) => o,
         Err(e) => {
             eprintln!("guardrails: biome: failed to execute: {}", e);
@@ -152,8 +167,6 @@ pub fn check(content: &str, file_path: &str) -> Vec<Violation> {
         }
     };
 
-    let line_offsets = build_line_offsets(content);
-
     biome_output
         .diagnostics
         .into_iter()
@@ -164,10 +177,11 @@ pub fn check(content: &str, file_path: &str) -> Vec<Violation> {
                 _ => Severity::Low,
             };
 
-            let line = d.location.span.as_ref().map(|span| {
-                let offset = span.first().copied().unwrap_or(0) as usize;
-                offset_to_line(&line_offsets, offset) as u32
-            });
+            let span = d
+                .location
+                .span
+                .as_deref()
+                .and_then(|offsets| to_span(content, offsets));
 
             let fix = get_fix_for_rule(&d.category)
                 .map(String::from)
@@ -178,12 +192,25 @@ pub fn check(content: &str, file_path: &str) -> Vec<Violation> {
                 severity,
                 failure: fix,
                 file: file_path.to_string(),
-                line,
+                line: span.map(|s| s.line),
+                column: span.map(|s| s.column),
+                end_line: span.map(|s| s.end_line),
+                end_column: span.map(|s| s.end_column),
             }
         })
         .collect()
 }
 
+/// Convert biome's `[start, end]` byte offsets into `content` to a span.
+fn to_span(content: &str, offsets: &[u32]) -> Option<Span> {
+    let start = *offsets.first()? as usize;
+    let end = offsets.get(1).map_or(start, |&e| e as usize);
+    if end < start || !content.is_char_boundary(start) || !content.is_char_boundary(end) {
+        return None;
+    }
+    Some(Span::in_text(1, content, start, end))
+}
+
 fn get_fix_for_rule(category: &str) -> Option<&'static str> {
     match category {
         "lint/security/noGlobalEval" => {
@@ -242,6 +269,23 @@ mod tests {
         assert!(get_fix_for_rule("lint/a11y/useAltText").is_some());
     }
 
+    #[test]
+    fn converts_span_offsets() {
+        let content = "const a = 1;\nlet é = eval(x);\n";
+        let start = content.find("eval").unwrap() as u32;
+        assert_eq!(
+            to_span(content, &[start, start + 7]),
+            Some(Span {
+                line: 2,
+                column: 9,
+                end_line: 2,
+                end_column: 16,
+            })
+        );
+        assert_eq!(to_span(content, &[0]).map(|s| s.end_column), Some(1));
+        assert_eq!(to_span(content, &[5, 500]), None);
+    }
+
     #[test]
     fn get_fix_for_unknown_rule() {
         assert!(get_fix_for_rule("unknown/rule").is_none());
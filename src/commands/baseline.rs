@@ -0,0 +1,217 @@
+//! `guardrails baseline`: record existing findings so only new ones are reported.
+
+use super::check::{check_files, collect_files, default_jobs, CheckOptions};
+use super::{usage_error, Args, EXIT_USAGE};
+use crate::baseline::{relative_path, repo_root, Baseline, BASELINE_FILE};
+use crate::rules::Violation;
+use crate::runner::Projects;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+enum Action {
+    Create,
+    Prune,
+}
+
+struct BaselineOptions {
+    action: Action,
+    no_biome: bool,
+}
+
+fn parse_options(args: &[String]) -> Result<BaselineOptions, String> {
+    let mut args = Args::new(args);
+    let action = match args.next() {
+        Some("create") => Action::Create,
+        Some("prune") => Action::Prune,
+        Some(other) => return Err(format!("unknown baseline action: {}", other)),
+        None => return Err("expected create or prune".to_string()),
+    };
+    let mut no_biome = false;
+    while let Some(arg) = args.next() {
+        match arg {
+            "--no-biome" => no_biome = true,
+            _ => return Err(format!("unexpected argument: {}", arg)),
+        }
+    }
+    Ok(BaselineOptions { action, no_biome })
+}
+
+/// Check `files` without the current baseline and record what is found.
+fn scan(root: &Path, projects: &Projects, files: &[PathBuf]) -> Baseline {
+    let checked = check_files(projects, files, default_jobs());
+
+    let mut by_file: BTreeMap<String, Vec<Violation>> = BTreeMap::new();
+    for v in checked.violations {
+        by_file.entry(v.file.clone()).or_default().push(v);
+    }
+
+    let mut findings = Vec::new();
+    for (path, violations) in by_file {
+        let (Some(file), Ok(content)) = (
+            relative_path(root, Path::new(&path)),
+            std::fs::read_to_string(&path),
+        ) else {
+            continue;
+        };
+        findings.push((file, content, violations));
+    }
+    Baseline::from_findings(
+        findings
+            .iter()
+            .map(|(file, content, v)| (file.as_str(), content.as_str(), v.as_slice())),
+    )
+}
+
+fn create(root: &Path, projects: &Projects) -> Result<String, String> {
+    let options = CheckOptions {
+        paths: vec![root.to_path_buf()],
+        ..CheckOptions::default()
+    };
+    let files = collect_files(&options)?;
+    let baseline = scan(root, projects, &files);
+    let path = root.join(BASELINE_FILE);
+    baseline.save(&path)?;
+
+    let count: usize = baseline.entries.iter().map(|e| e.count).sum();
+    Ok(format!(
+        "Recorded {} findings from {} files in {}",
+        count,
+        files.len(),
+        path.display()
+    ))
+}
+
+fn prune(root: &Path, projects: &Projects) -> Result<String, String> {
+    let path = root.join(BASELINE_FILE);
+    let mut baseline =
+        Baseline::load(&path)?.ok_or_else(|| format!("no baseline at {}", path.display()))?;
+
+    // Entries are sorted by rule first, so one file can appear several times.
+    let files: BTreeSet<PathBuf> = baseline
+        .entries
+        .iter()
+        .map(|e| root.join(&e.file))
+        .filter(|p| p.is_file())
+        .collect();
+    let files: Vec<PathBuf> = files.into_iter().collect();
+    let current = scan(root, projects, &files);
+    let removed = baseline.prune(&current);
+    baseline.save(&path)?;
+
+    Ok(format!(
+        "Removed {} fixed findings; {} entries remain in {}",
+        removed,
+        baseline.entries.len(),
+        path.display()
+    ))
+}
+
+pub fn run(args: &[String]) -> i32 {
+    let options = match parse_options(args) {
+        Ok(o) => o,
+        Err(e) => return usage_error(&e),
+    };
+
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let Some(root) = repo_root(&cwd) else {
+        eprintln!("guardrails: not inside a git repository");
+        return EXIT_USAGE;
+    };
+
+    let projects = Projects::new(options.no_biome).without_baseline();
+    let result = match options.action {
+        Action::Create => create(&root, &projects),
+        Action::Prune => prune(&root, &projects),
+    };
+    match result {
+        Ok(summary) => {
+            println!("{}", summary);
+            0
+        }
+        Err(e) => {
+            eprintln!("guardrails: {}", e);
+            EXIT_USAGE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn write(dir: &Path, name: &str, content: &str) {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn parses_actions() {
+        assert!(matches!(
+            parse_options(&args(&["create", "--no-biome"])),
+            Ok(BaselineOptions {
+                action: Action::Create,
+                no_biome: true
+            })
+        ));
+        assert!(parse_options(&args(&["prune"])).is_ok());
+        assert!(parse_options(&[]).is_err());
+        assert!(parse_options(&args(&["update"])).is_err());
+    }
+
+    #[test]
+    fn create_then_prune() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir(root.join(".git")).unwrap();
+        write(root, "src/a.ts", "el.innerHTML = x;\n");
+        write(root, "src/b.ts", "const data = fs.readFileSync(p);\n");
+
+        let projects = Projects::new(true).without_baseline();
+        create(root, &projects).unwrap();
+
+        let baseline = Baseline::load(&root.join(BASELINE_FILE)).unwrap().unwrap();
+        let files: Vec<&str> = baseline.entries.iter().map(|e| e.file.as_str()).collect();
+        assert!(files.contains(&"src/a.ts"));
+        assert!(files.contains(&"src/b.ts"));
+
+        write(root, "src/a.ts", "el.textContent = x;\n");
+        prune(root, &projects).unwrap();
+        let baseline = Baseline::load(&root.join(BASELINE_FILE)).unwrap().unwrap();
+        assert!(baseline.entries.iter().all(|e| e.file == "src/b.ts"));
+        assert!(!baseline.entries.is_empty());
+    }
+
+    #[test]
+    fn prune_scans_each_file_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir(root.join(".git")).unwrap();
+        // Entries sort as (dom-access, a), (dom-access, b), (sync-io, a).
+        let read = "const data = fs.readFileSync(p);\n";
+        write(
+            root,
+            "src/a.ts",
+            &format!("el.innerHTML = x;\n{}{}", read, read),
+        );
+        write(root, "src/b.ts", "el.innerHTML = y;\n");
+
+        let projects = Projects::new(true).without_baseline();
+        create(root, &projects).unwrap();
+
+        write(root, "src/a.ts", &format!("el.innerHTML = x;\n{}", read));
+        prune(root, &projects).unwrap();
+        let baseline = Baseline::load(&root.join(BASELINE_FILE)).unwrap().unwrap();
+        let sync_io: Vec<usize> = baseline
+            .entries
+            .iter()
+            .filter(|e| e.rule == "sync-io")
+            .map(|e| e.count)
+            .collect();
+        assert_eq!(sync_io, [1]);
+    }
+}
@@ -0,0 +1,260 @@
+//! `guardrails check`: scan files and directories with the same rules as the hook.
+
+use super::{usage_error, Args, EXIT_USAGE};
+use crate::config::Config;
+use crate::reporter::{format_check_report, Report};
+use crate::runner::{Checked, Projects};
+use ignore::overrides::OverrideBuilder;
+use ignore::WalkBuilder;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+const MAX_FILE_SIZE: u64 = 10_000_000; // 10MB limit
+
+#[derive(Default)]
+pub(super) struct CheckOptions {
+    pub paths: Vec<PathBuf>,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub no_biome: bool,
+    pub jobs: Option<usize>,
+}
+
+fn parse_options(args: &[String]) -> Result<CheckOptions, String> {
+    let mut options = CheckOptions::default();
+    let mut args = Args::new(args);
+
+    while let Some(arg) = args.next() {
+        match arg {
+            "--include" => options.include.push(args.value(arg)?.to_string()),
+            "--exclude" => options.exclude.push(args.value(arg)?.to_string()),
+            "--no-biome" => options.no_biome = true,
+            "--jobs" | "-j" => {
+                let value = args.value(arg)?;
+                let jobs = value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&n| n > 0)
+                    .ok_or_else(|| format!("invalid --jobs value: {}", value))?;
+                options.jobs = Some(jobs);
+            }
+            _ if arg.starts_with('-') => return Err(format!("unknown option: {}", arg)),
+            _ => options.paths.push(PathBuf::from(arg)),
+        }
+    }
+
+    if options.paths.is_empty() {
+        options.paths.push(PathBuf::from("."));
+    }
+    Ok(options)
+}
+
+/// Collect files under `paths`, honoring `.gitignore` and the include/exclude globs.
+pub(super) fn collect_files(options: &CheckOptions) -> Result<Vec<PathBuf>, String> {
+    let mut overrides = OverrideBuilder::new(".");
+    for glob in &options.include {
+        overrides
+            .add(glob)
+            .map_err(|e| format!("invalid --include glob {:?}: {}", glob, e))?;
+    }
+    for glob in &options.exclude {
+        overrides
+            .add(&format!("!{}", glob))
+            .map_err(|e| format!("invalid --exclude glob {:?}: {}", glob, e))?;
+    }
+    let overrides = overrides.build().map_err(|e| e.to_string())?;
+
+    let (first, rest) = options
+        .paths
+        .split_first()
+        .expect("parse_options ensures at least one path");
+    let mut builder = WalkBuilder::new(first);
+    for path in rest {
+        builder.add(path);
+    }
+    builder.overrides(overrides);
+
+    let mut files = Vec::new();
+    for entry in builder.build() {
+        match entry {
+            Ok(entry) if entry.file_type().is_some_and(|t| t.is_file()) => {
+                files.push(entry.into_path())
+            }
+            Ok(_) => {}
+            Err(e) => eprintln!("guardrails: {}", e),
+        }
+    }
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+fn check_file(projects: &Projects, path: &Path) -> Checked {
+    if std::fs::metadata(path).is_ok_and(|m| m.len() > MAX_FILE_SIZE) {
+        eprintln!("guardrails: skipping {} (too large)", path.display());
+        return Checked::default();
+    }
+    // Binary and non-UTF-8 files are not checked.
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Checked::default();
+    };
+    let file_path = path.to_string_lossy();
+    projects
+        .for_file(path)
+        .runner
+        .check_suppressed(&file_path, &content)
+}
+
+/// Check files on scoped worker threads pulling from a shared index.
+pub(super) fn check_files(projects: &Projects, files: &[PathBuf], jobs: usize) -> Checked {
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Checked::default());
+
+    std::thread::scope(|scope| {
+        for _ in 0..jobs.min(files.len()).max(1) {
+            scope.spawn(|| {
+                let mut local = Checked::default();
+                while let Some(path) = files.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    local.extend(check_file(projects, path));
+                }
+                results.lock().expect("results lock poisoned").extend(local);
+            });
+        }
+    });
+
+    let mut checked = results.into_inner().expect("results lock poisoned");
+    checked.sort();
+    checked
+}
+
+pub(super) fn default_jobs() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+pub fn run(args: &[String]) -> i32 {
+    let options = match parse_options(args) {
+        Ok(o) => o,
+        Err(e) => return usage_error(&e),
+    };
+
+    // Report settings come from the current directory; rules and decisions
+    // from each file's own project config.
+    let config = Config::load_for(Path::new("."));
+
+    let files = match collect_files(&options) {
+        Ok(f) => f,
+        Err(e) => {
+            eprintln!("guardrails: {}", e);
+            return EXIT_USAGE;
+        }
+    };
+
+    let jobs = options.jobs.unwrap_or_else(default_jobs);
+    let projects = Projects::new(options.no_biome);
+    let checked = check_files(&projects, &files, jobs);
+    let report = Report::with_decisions(&checked.violations, &config, |v| projects.decision(v))
+        .with_suppressed(&checked.suppressed);
+
+    println!("{}", format_check_report(&report, files.len()));
+
+    if report.deny.is_empty() && report.ask.is_empty() {
+        0
+    } else {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn write(dir: &Path, name: &str, content: &str) {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn parses_options() {
+        let options = parse_options(&args(&[
+            "src",
+            "--include",
+            "*.ts",
+            "--exclude",
+            "**/vendor/**",
+            "--no-biome",
+            "-j",
+            "4",
+        ]))
+        .unwrap();
+        assert_eq!(options.paths, [PathBuf::from("src")]);
+        assert_eq!(options.include, ["*.ts"]);
+        assert_eq!(options.exclude, ["**/vendor/**"]);
+        assert!(options.no_biome);
+        assert_eq!(options.jobs, Some(4));
+    }
+
+    #[test]
+    fn rejects_bad_options() {
+        assert!(parse_options(&args(&["--jobs", "0"])).is_err());
+        assert!(parse_options(&args(&["--include"])).is_err());
+        assert!(parse_options(&args(&["--bogus"])).is_err());
+    }
+
+    #[test]
+    fn defaults_to_current_directory() {
+        let options = parse_options(&[]).unwrap();
+        assert_eq!(options.paths, [PathBuf::from(".")]);
+    }
+
+    #[test]
+    fn walks_with_gitignore_and_globs() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir(root.join(".git")).unwrap();
+        write(root, ".gitignore", "dist/\n");
+        write(root, "src/a.ts", "");
+        write(root, "src/b.js", "");
+        write(root, "src/vendor/c.ts", "");
+        write(root, "dist/d.ts", "");
+
+        let options = CheckOptions {
+            paths: vec![root.to_path_buf()],
+            include: vec!["*.ts".to_string()],
+            exclude: vec!["**/vendor/**".to_string()],
+            ..Default::default()
+        };
+        let files = collect_files(&options).unwrap();
+        let names: Vec<_> = files
+            .iter()
+            .map(|f| f.strip_prefix(root).unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, ["src/a.ts"]);
+    }
+
+    #[test]
+    fn checks_files_in_parallel() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, "src/a.ts", "el.innerHTML = x;\n");
+        write(root, "src/b.ts", "const ok = 1;\n");
+        write(root, "src/c.ts", "el.innerHTML = y;\n");
+
+        let files = vec![
+            root.join("src/a.ts"),
+            root.join("src/b.ts"),
+            root.join("src/c.ts"),
+        ];
+        let violations = check_files(&Projects::new(true), &files, 2).violations;
+        assert_eq!(violations.len(), 2);
+        assert!(violations[0].file.ends_with("a.ts"));
+        assert!(violations[1].file.ends_with("c.ts"));
+    }
+}
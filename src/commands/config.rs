@@ -0,0 +1,200 @@
+//! `guardrails config --explain <file>`: show how the effective config for a file
+//! is assembled from the user config and project `.guardrails.json` files.
+
+use super::{usage_error, Args};
+use crate::config::{Config, ConfigLayer, Override};
+use serde_json::Value;
+use std::path::Path;
+
+/// Flatten nested objects into `(dotted.key, value)` leaves.
+fn leaves(prefix: &str, value: &Value, out: &mut Vec<(String, Value)>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                leaves(&key, value, out);
+            }
+        }
+        _ => out.push((prefix.to_string(), value.clone())),
+    }
+}
+
+/// Each setting with the layer that decides it. Later layers win.
+fn effective_settings(layers: &[ConfigLayer]) -> Vec<(String, Value, usize)> {
+    let mut settings: Vec<(String, Value, usize)> = Vec::new();
+    for (index, layer) in layers.iter().enumerate() {
+        let mut layer_leaves = Vec::new();
+        leaves("", &layer.value, &mut layer_leaves);
+        for (key, value) in layer_leaves {
+            // A replaced array or scalar hides anything nested under the old key.
+            settings.retain(|(k, _, _)| k != &key && !k.starts_with(&format!("{}.", key)));
+            settings.push((key, value, index));
+        }
+    }
+    settings.sort_by(|a, b| a.0.cmp(&b.0));
+    settings
+}
+
+fn format_explain(file: &Path, layers: &[ConfigLayer], config: &Config) -> String {
+    let mut lines = vec![format!("Config for {}", file.display()), String::new()];
+
+    lines.push("Sources (later entries override earlier ones):".to_string());
+    lines.push("  0. built-in defaults".to_string());
+    for (index, layer) in layers.iter().enumerate() {
+        lines.push(format!("  {}. {}", index + 1, layer.path.display()));
+    }
+
+    let settings = effective_settings(layers);
+    lines.push(String::new());
+    if settings.is_empty() {
+        lines.push("No settings changed from the defaults.".to_string());
+    } else {
+        lines.push("Settings:".to_string());
+        let width = settings
+            .iter()
+            .map(|(k, v, _)| k.len() + v.to_string().len())
+            .max();
+        for (key, value, index) in &settings {
+            let setting = format!("{} = {}", key, value);
+            lines.push(format!(
+                "  {:<width$}  ({})",
+                setting,
+                index + 1,
+                width = width.unwrap_or(0) + 3
+            ));
+        }
+    }
+
+    if !config.overrides.is_empty() {
+        let absolute = std::env::current_dir()
+            .map(|cwd| cwd.join(file))
+            .unwrap_or_else(|_| file.to_path_buf());
+        let matched = config.matching_overrides(&absolute.to_string_lossy());
+        lines.push(String::new());
+        if matched.is_empty() {
+            lines.push("No overrides match this file.".to_string());
+        } else {
+            lines.push("Overrides applied to this file (in order):".to_string());
+            for index in matched {
+                lines.push(format!(
+                    "  overrides[{}]: {}",
+                    index,
+                    describe_override(&config.overrides[index])
+                ));
+            }
+        }
+    }
+
+    lines.join("\n")
+}
+
+fn describe_override(o: &Override) -> String {
+    let mut parts = vec![format!("files {:?}", o.files)];
+    if !o.excludes.is_empty() {
+        parts.push(format!("excludes {:?}", o.excludes));
+    }
+    if !o.rules.is_empty() {
+        parts.push(format!("rules {}", Value::Object(o.rules.clone())));
+    }
+    if !o.severity.is_empty() {
+        parts.push(format!("severity {}", Value::Object(o.severity.clone())));
+    }
+    parts.join(", ")
+}
+
+pub fn run(args: &[String]) -> i32 {
+    let mut args = Args::new(args);
+    let mut file = None;
+    while let Some(arg) = args.next() {
+        match arg {
+            "--explain" => match args.value(arg) {
+                Ok(f) => file = Some(f),
+                Err(e) => return usage_error(&e),
+            },
+            _ => return usage_error(&format!("unexpected argument: {}", arg)),
+        }
+    }
+    let Some(file) = file else {
+        return usage_error("config requires --explain <file>");
+    };
+
+    let file = Path::new(file);
+    let resolved = Config::resolve(file);
+    println!(
+        "{}",
+        format_explain(file, &resolved.layers, &resolved.config)
+    );
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::path::PathBuf;
+
+    fn layer(path: &str, value: Value) -> ConfigLayer {
+        ConfigLayer {
+            path: PathBuf::from(path),
+            value,
+        }
+    }
+
+    #[test]
+    fn later_layers_decide_settings() {
+        let layers = [
+            layer(
+                "user.json",
+                json!({"rules": {"syncIo": false, "naming": false}}),
+            ),
+            layer("repo/.guardrails.json", json!({"rules": {"naming": true}})),
+        ];
+        let settings = effective_settings(&layers);
+        assert_eq!(
+            settings,
+            [
+                ("rules.naming".to_string(), json!(true), 1),
+                ("rules.syncIo".to_string(), json!(false), 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn replaced_value_hides_nested_keys() {
+        let layers = [
+            layer("a", json!({"severity": {"rules": {"x": "ask"}}})),
+            layer("b", json!({"severity": {"rules": null}})),
+        ];
+        let settings = effective_settings(&layers);
+        assert_eq!(settings, [("severity.rules".to_string(), Value::Null, 1)]);
+    }
+
+    #[test]
+    fn explain_lists_sources_in_order() {
+        let layers = [
+            layer("/home/u/.config/guardrails/config.json", json!({})),
+            layer("/repo/.guardrails.json", json!({"output": "json"})),
+        ];
+        let text = format_explain(Path::new("/repo/a.ts"), &layers, &Config::default());
+        assert!(text.contains("  1. /home/u/.config/guardrails/config.json"));
+        assert!(text.contains("  2. /repo/.guardrails.json"));
+        assert!(text.contains("output = \"json\""));
+        assert!(text.trim_end().ends_with("(2)"));
+    }
+
+    #[test]
+    fn explain_shows_matching_overrides() {
+        let config: Config = serde_json::from_str(
+            r#"{"overrides": [{"files": ["packages/cli/**"], "rules": {"syncIo": false}}]}"#,
+        )
+        .unwrap();
+        let text = format_explain(Path::new("/repo/packages/cli/a.ts"), &[], &config);
+        assert!(text.contains(r#"overrides[0]: files ["packages/cli/**"], rules {"syncIo":false}"#));
+        let text = format_explain(Path::new("/repo/apps/a.ts"), &[], &config);
+        assert!(text.contains("No overrides match this file."));
+    }
+}
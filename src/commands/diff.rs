@@ -0,0 +1,134 @@
+//! `guardrails diff`: check staged changes or a commit range, reporting only
+//! violations on added or modified lines.
+
+use super::{usage_error, Args, EXIT_USAGE};
+use crate::config::Config;
+use crate::git::{DiffRange, Repo};
+use crate::reporter::{format_check_report, Report};
+use crate::rules::Violation;
+use crate::runner::{Checked, Projects};
+
+struct DiffOptions {
+    range: DiffRange,
+    no_biome: bool,
+}
+
+fn parse_options(args: &[String]) -> Result<DiffOptions, String> {
+    let mut range = None;
+    let mut no_biome = false;
+    let mut args = Args::new(args);
+
+    while let Some(arg) = args.next() {
+        let parsed = match arg {
+            "--staged" | "--cached" => DiffRange::Staged,
+            "--no-biome" => {
+                no_biome = true;
+                continue;
+            }
+            _ if arg.starts_with('-') => return Err(format!("unknown option: {}", arg)),
+            _ => DiffRange::parse(arg)?,
+        };
+        if range.replace(parsed).is_some() {
+            return Err("only one of --staged or <base>..<head> may be given".to_string());
+        }
+    }
+
+    Ok(DiffOptions {
+        range: range.ok_or("expected --staged or <base>..<head>")?,
+        no_biome,
+    })
+}
+
+/// Check one changed file. Path-based violations (no line) always apply;
+/// line violations only when they fall on an added line.
+fn check_changed_file(
+    repo: &Repo,
+    projects: &Projects,
+    range: &DiffRange,
+    path: &str,
+) -> Result<Checked, String> {
+    let content = repo.read_blob(range, path)?;
+    let added = repo.added_lines(range, path)?;
+    let file = repo.root().join(path);
+    let file_path = file.to_string_lossy();
+
+    let on_added = |v: &Violation| v.line.is_none_or(|l| added.contains(&l));
+    let mut checked = projects
+        .for_file(&file)
+        .runner
+        .check_suppressed(&file_path, &content);
+    checked.violations.retain(|v| on_added(v));
+    checked.suppressed.retain(|s| on_added(&s.violation));
+    Ok(checked)
+}
+
+pub fn run(args: &[String]) -> i32 {
+    let options = match parse_options(args) {
+        Ok(o) => o,
+        Err(e) => return usage_error(&e),
+    };
+
+    let repo = match Repo::discover() {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("guardrails: {}", e);
+            return EXIT_USAGE;
+        }
+    };
+
+    // Report settings come from the repo root; rules and decisions from
+    // each file's own project config.
+    let config = Config::load_for(repo.root());
+
+    let files = match repo.changed_files(&options.range) {
+        Ok(f) => f,
+        Err(e) => {
+            eprintln!("guardrails: {}", e);
+            return EXIT_USAGE;
+        }
+    };
+
+    let projects = Projects::new(options.no_biome);
+    let mut checked = Checked::default();
+    for path in &files {
+        match check_changed_file(&repo, &projects, &options.range, path) {
+            Ok(c) => checked.extend(c),
+            Err(e) => eprintln!("guardrails: skipping {}: {}", path, e),
+        }
+    }
+
+    let report = Report::with_decisions(&checked.violations, &config, |v| projects.decision(v))
+        .with_suppressed(&checked.suppressed);
+    println!("{}", format_check_report(&report, files.len()));
+
+    if report.deny.is_empty() && report.ask.is_empty() {
+        0
+    } else {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_staged_and_ranges() {
+        let options = parse_options(&args(&["--staged", "--no-biome"])).unwrap();
+        assert_eq!(options.range, DiffRange::Staged);
+        assert!(options.no_biome);
+
+        let options = parse_options(&args(&["main..HEAD"])).unwrap();
+        assert!(matches!(options.range, DiffRange::Commits { .. }));
+    }
+
+    #[test]
+    fn rejects_missing_or_conflicting_ranges() {
+        assert!(parse_options(&[]).is_err());
+        assert!(parse_options(&args(&["--staged", "main..HEAD"])).is_err());
+    }
+}
@@ -0,0 +1,355 @@
+//! `guardrails explain <rule>`: why a rule exists, what it flags and allows,
+//! and the config keys that tune it.
+
+use super::{usage_error, Args, EXIT_USAGE};
+use crate::config::{Config, CustomRule};
+use crate::rules::{self, Category, Example, Language, RuleMeta, Severity};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Debug, PartialEq)]
+struct ExplainOptions {
+    rule: String,
+    format: Format,
+}
+
+fn parse_options(args: &[String]) -> Result<ExplainOptions, String> {
+    let mut args = Args::new(args);
+    let mut rule = None;
+    let mut format = Format::Text;
+    while let Some(arg) = args.next() {
+        match arg {
+            "--format" => {
+                format = match args.value(arg)? {
+                    "text" => Format::Text,
+                    "json" => Format::Json,
+                    other => return Err(format!("unknown format: {}", other)),
+                }
+            }
+            _ if arg.starts_with('-') => return Err(format!("unexpected argument: {}", arg)),
+            _ if rule.is_some() => return Err(format!("unexpected argument: {}", arg)),
+            _ => rule = Some(arg.to_string()),
+        }
+    }
+    let rule = rule.ok_or("explain requires a rule name")?;
+    Ok(ExplainOptions { rule, format })
+}
+
+#[derive(Debug, Serialize)]
+struct ConfigKey {
+    key: String,
+    description: String,
+}
+
+impl ConfigKey {
+    fn new(key: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            description: description.into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Explanation<'a> {
+    id: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key: Option<&'static str>,
+    aliases: &'a [&'static str],
+    source: &'static str,
+    category: Category,
+    severity: Severity,
+    languages: &'a [Language],
+    purpose: &'a str,
+    detects: String,
+    flags: Vec<&'a Example>,
+    allows: Vec<&'a Example>,
+    when_to_disable: &'a str,
+    config: Vec<ConfigKey>,
+}
+
+impl<'a> Explanation<'a> {
+    fn new(meta: &'a RuleMeta, source: &'static str) -> Self {
+        Self {
+            id: &meta.id,
+            key: None,
+            aliases: meta.aliases,
+            source,
+            category: meta.category,
+            severity: meta.severity,
+            languages: meta.languages,
+            purpose: meta.rationale,
+            detects: meta.description.to_string(),
+            flags: meta.examples.iter().filter(|e| e.flagged).collect(),
+            allows: meta.examples.iter().filter(|e| !e.flagged).collect(),
+            when_to_disable: meta.disable_when,
+            config: Vec::new(),
+        }
+    }
+}
+
+fn builtin(registration: &'static rules::Registration) -> Explanation<'static> {
+    let meta = registration.meta;
+    let key = registration.key;
+    let mut explanation = Explanation::new(meta, "guardrails");
+    explanation.key = Some(key);
+
+    let decision_key = if key == "biome" {
+        "severity.rules.biome/lint/<group>/<rule>".to_string()
+    } else {
+        format!("severity.rules.{}", meta.id)
+    };
+    explanation.config = vec![
+        ConfigKey::new(
+            format!("rules.{}", key),
+            "true/false, \"off\", a severity, or {\"enabled\": ..., \"severity\": ...}",
+        ),
+        ConfigKey::new(
+            decision_key,
+            "\"allow\", \"ask\" or \"deny\", whatever the severity",
+        ),
+        ConfigKey::new(
+            format!("overrides[].rules.{}", key),
+            format!("Like rules.{}, for matching paths only", key),
+        ),
+    ];
+    explanation.config.extend(
+        meta.settings
+            .iter()
+            .map(|s| ConfigKey::new(s.key, s.description)),
+    );
+    if meta.checks_content {
+        explanation.config.push(ConfigKey::new(
+            format!("// guardrails-ignore-next-line {} -- <reason>", key),
+            "Accept one finding in the code",
+        ));
+    }
+    explanation
+}
+
+fn custom<'a>(meta: &'a RuleMeta, rule: &CustomRule, index: usize) -> Explanation<'a> {
+    let mut explanation = Explanation::new(meta, "custom");
+    explanation.purpose = "Project rule declared under customRules.";
+    explanation.detects = format!("Lines matching {}", rule.patterns.join(" or "));
+    if !rule.requires.is_empty() {
+        explanation.detects += &format!(", in files that match {}", rule.requires.join(" and "));
+    }
+    if !rule.unless.is_empty() {
+        explanation.detects += &format!(", unless the file matches {}", rule.unless.join(" or "));
+    }
+    explanation.config = vec![
+        ConfigKey::new(
+            format!("customRules[{}]", index),
+            "The rule itself: files, patterns, requires, unless, severity, fix",
+        ),
+        ConfigKey::new(
+            format!("severity.rules.{}", meta.id),
+            "\"allow\", \"ask\" or \"deny\", whatever the severity",
+        ),
+        ConfigKey::new(
+            format!("// guardrails-ignore-next-line {} -- <reason>", meta.id),
+            "Accept one finding in the code",
+        ),
+    ];
+    explanation
+}
+
+fn push_examples(lines: &mut Vec<String>, title: &str, examples: &[&Example]) {
+    if examples.is_empty() {
+        return;
+    }
+    lines.push(String::new());
+    lines.push(title.to_string());
+    for example in examples {
+        let mut code: Vec<&str> = example.code.lines().collect();
+        if code.is_empty() {
+            code.push("(any content)");
+        }
+        if !example.path.is_empty() {
+            lines.push(format!("  {}", example.path));
+        }
+        for line in code {
+            lines.push(format!("    {}", line));
+        }
+    }
+}
+
+fn format_text(e: &Explanation) -> String {
+    let mut title = e.id.to_string();
+    if let Some(key) = e.key.filter(|k| *k != e.id) {
+        title.push_str(&format!(" (config key: {})", key));
+    }
+    let languages: Vec<String> = e.languages.iter().map(|l| l.to_string()).collect();
+    let mut lines = vec![
+        title,
+        format!(
+            "{} rule, {}, default severity {}, {}",
+            e.source,
+            e.category,
+            e.severity,
+            languages.join("/")
+        ),
+        String::new(),
+        "Why".to_string(),
+        format!("  {}", e.purpose),
+        String::new(),
+        "What it detects".to_string(),
+        format!("  {}", e.detects),
+    ];
+
+    push_examples(&mut lines, "Flagged", &e.flags);
+    push_examples(&mut lines, "Allowed", &e.allows);
+
+    if !e.when_to_disable.is_empty() {
+        lines.push(String::new());
+        lines.push("When to disable".to_string());
+        lines.push(format!("  {}", e.when_to_disable));
+    }
+
+    lines.push(String::new());
+    lines.push("Configuration".to_string());
+    let width = e.config.iter().map(|c| c.key.len()).max().unwrap_or(0);
+    for c in &e.config {
+        lines.push(format!(
+            "  {:<width$}  {}",
+            c.key,
+            c.description,
+            width = width
+        ));
+    }
+
+    lines.join("\n")
+}
+
+pub fn run(args: &[String]) -> i32 {
+    let options = match parse_options(args) {
+        Ok(o) => o,
+        Err(e) => return usage_error(&e),
+    };
+
+    let config = Config::load_for(&std::env::current_dir().unwrap_or_default());
+    let custom_rules = rules::custom_rules(&config);
+    let name = if options.rule.starts_with("biome/") {
+        "biome"
+    } else {
+        options.rule.as_str()
+    };
+
+    let explanation = if let Some(registration) = rules::lookup(name) {
+        builtin(registration)
+    } else if let Some(index) = config.custom_rules.iter().position(|c| c.id == name) {
+        let Some(compiled) = custom_rules
+            .iter()
+            .find(|c| rules::Rule::meta(*c).id == name)
+        else {
+            eprintln!("guardrails: custom rule {:?} does not compile", name);
+            return EXIT_USAGE;
+        };
+        custom(
+            rules::Rule::meta(compiled),
+            &config.custom_rules[index],
+            index,
+        )
+    } else {
+        eprintln!(
+            "guardrails: unknown rule {:?}; run `guardrails rules list` to see them",
+            options.rule
+        );
+        return EXIT_USAGE;
+    };
+
+    match options.format {
+        Format::Text => println!("{}", format_text(&explanation)),
+        Format::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&explanation).unwrap_or_default()
+        ),
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_rule_and_format() {
+        assert_eq!(
+            parse_options(&args(&["naming", "--format", "json"])),
+            Ok(ExplainOptions {
+                rule: "naming".to_string(),
+                format: Format::Json
+            })
+        );
+        assert!(parse_options(&[]).is_err());
+        assert!(parse_options(&args(&["naming", "syncIo"])).is_err());
+    }
+
+    #[test]
+    fn explains_builtin_rule() {
+        let text = format_text(&builtin(rules::lookup("transaction-boundary").unwrap()));
+        assert!(text.starts_with("transaction-boundary (config key: transaction)"));
+        assert!(text.contains("What it detects"));
+        assert!(text.contains("Flagged\n  /src/usecases/handler.ts\n    async function handle() {"));
+        assert!(text.contains("When to disable\n  Non-database projects"));
+        assert!(text.contains("rules.transaction "));
+        assert!(text.contains("severity.rules.transaction-boundary"));
+    }
+
+    #[test]
+    fn suppression_hint_only_for_content_rules() {
+        let hint = |name| {
+            format_text(&builtin(rules::lookup(name).unwrap())).contains("guardrails-ignore")
+        };
+        assert!(hint("syncIo"));
+        for name in [
+            "sensitiveFile",
+            "generatedFile",
+            "testLocation",
+            "sensitiveRead",
+            "bash",
+        ] {
+            assert!(!hint(name), "{} findings can't be suppressed", name);
+        }
+    }
+
+    #[test]
+    fn json_lists_examples_and_settings() {
+        let json = serde_json::to_value(builtin(rules::lookup("security").unwrap())).unwrap();
+        assert_eq!(json["key"], "security");
+        assert_eq!(json["flags"][0]["code"], "element.innerHTML = userInput;");
+        assert!(!json["allows"].as_array().unwrap().is_empty());
+        assert_eq!(json["whenToDisable"], "Never (security critical)");
+        let keys: Vec<&str> = json["config"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|c| c["key"].as_str())
+            .collect();
+        assert!(keys.contains(&"rules.security.patterns.<id>"));
+    }
+
+    #[test]
+    fn explains_custom_rule() {
+        let config: Config = serde_json::from_str(
+            r#"{"customRules": [{"id": "no-axios", "patterns": ["from 'axios'"], "fix": "Use src/lib/http.ts"}]}"#,
+        )
+        .unwrap();
+        let compiled = rules::custom_rules(&config);
+        let e = custom(rules::Rule::meta(&compiled[0]), &config.custom_rules[0], 0);
+        let text = format_text(&e);
+        assert!(text.contains("custom rule"));
+        assert!(text.contains("Lines matching from 'axios'"));
+        assert!(text.contains("customRules[0]"));
+    }
+}
@@ -0,0 +1,95 @@
+//! Subcommands for running guardrails outside the Claude Code hook.
+//!
+//! Without a recognized subcommand the binary runs as a hook and reads the
+//! tool payload from stdin.
+
+mod baseline;
+mod check;
+mod config;
+mod diff;
+mod explain;
+mod rules;
+
+/// Exit code for invalid arguments or runtime errors.
+const EXIT_USAGE: i32 = 2;
+
+/// Run a subcommand. Returns None if `args` does not name one.
+pub fn run(args: &[String]) -> Option<i32> {
+    let (command, rest) = args.split_first()?;
+    let code = match command.as_str() {
+        "check" => check::run(rest),
+        "diff" => diff::run(rest),
+        "lsp" => match rest.first().map(String::as_str) {
+            None => crate::lsp::run(true),
+            Some("--no-biome") if rest.len() == 1 => crate::lsp::run(false),
+            Some(arg) => usage_error(&format!("unexpected argument: {}", arg)),
+        },
+        "baseline" => baseline::run(rest),
+        "config" => config::run(rest),
+        "rules" => rules::run(rest),
+        "explain" => explain::run(rest),
+        #[cfg(unix)]
+        "serve" => match rest.first() {
+            Some(arg) => usage_error(&format!("unexpected argument: {}", arg)),
+            None => crate::daemon::serve(),
+        },
+        "help" | "--help" | "-h" => {
+            println!("{}", usage());
+            0
+        }
+        _ => return None,
+    };
+    Some(code)
+}
+
+fn usage() -> &'static str {
+    "Usage:
+  guardrails                       Run as a Claude Code hook (reads JSON from stdin)
+  guardrails check [paths...]      Scan files and directories with the guardrails rules
+      --include <glob>             Only check matching files (repeatable)
+      --exclude <glob>             Skip matching files (repeatable)
+      --no-biome                   Skip biome checks
+      --jobs <n>                   Number of parallel workers
+  guardrails diff --staged         Check staged changes (added/modified lines only)
+  guardrails diff <base>..<head>   Check a commit range (added/modified lines only)
+      --no-biome                   Skip biome checks
+  guardrails baseline create       Record current findings in .guardrails-baseline.json
+  guardrails baseline prune        Drop baseline entries that have been fixed
+      --no-biome                   Skip biome checks
+  guardrails config --explain <file>
+                                   Show which config files apply to a file and what they set
+  guardrails rules list            List every rule with its config key, category and state
+      --format <text|json>         Output format (default: text)
+  guardrails explain <rule>        Explain a rule: why it exists, examples, how to tune it
+      --format <text|json>         Output format (default: text)
+  guardrails lsp                   Run a language server on stdio
+      --no-biome                   Skip biome checks
+  guardrails serve                 Run the hook daemon (started automatically)"
+}
+
+/// Minimal argument cursor shared by subcommands.
+pub(crate) struct Args<'a> {
+    args: std::slice::Iter<'a, String>,
+}
+
+impl<'a> Args<'a> {
+    pub fn new(args: &'a [String]) -> Self {
+        Self { args: args.iter() }
+    }
+
+    pub fn next(&mut self) -> Option<&'a str> {
+        self.args.next().map(String::as_str)
+    }
+
+    /// Value for an option like `--include <glob>`.
+    pub fn value(&mut self, option: &str) -> Result<&'a str, String> {
+        self.next()
+            .ok_or_else(|| format!("missing value for {}", option))
+    }
+}
+
+pub(crate) fn usage_error(message: &str) -> i32 {
+    eprintln!("guardrails: {}", message);
+    eprintln!("{}", usage());
+    EXIT_USAGE
+}
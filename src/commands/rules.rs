@@ -0,0 +1,157 @@
+//! `guardrails rules list`: the built-in and custom rules with their config state.
+
+use super::{usage_error, Args};
+use crate::config::Config;
+use crate::rules::{self, Category, Language, Rule, RuleMeta, Severity, REGISTRY};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Text,
+    Json,
+}
+
+fn parse_options(args: &[String]) -> Result<Format, String> {
+    let mut args = Args::new(args);
+    match args.next() {
+        Some("list") => {}
+        Some(other) => return Err(format!("unknown rules action: {}", other)),
+        None => return Err("expected list".to_string()),
+    }
+    let mut format = Format::Text;
+    while let Some(arg) = args.next() {
+        match arg {
+            "--format" => {
+                format = match args.value(arg)? {
+                    "text" => Format::Text,
+                    "json" => Format::Json,
+                    other => return Err(format!("unknown format: {}", other)),
+                }
+            }
+            _ => return Err(format!("unexpected argument: {}", arg)),
+        }
+    }
+    Ok(format)
+}
+
+#[derive(Serialize)]
+struct Listed<'a> {
+    id: &'a str,
+    /// Key under `rules` in config; None for custom rules.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key: Option<&'static str>,
+    aliases: &'a [&'static str],
+    source: &'static str,
+    category: Category,
+    severity: Severity,
+    languages: &'a [Language],
+    enabled: bool,
+    description: &'a str,
+}
+
+impl<'a> Listed<'a> {
+    fn new(meta: &'a RuleMeta, key: Option<&'static str>, severity: Option<Severity>) -> Self {
+        Self {
+            id: &meta.id,
+            key,
+            aliases: meta.aliases,
+            source: if key.is_some() {
+                "guardrails"
+            } else {
+                "custom"
+            },
+            category: meta.category,
+            severity: severity.unwrap_or(meta.severity),
+            languages: meta.languages,
+            enabled: true,
+            description: &meta.description,
+        }
+    }
+}
+
+fn format_text(listed: &[Listed]) -> String {
+    let width = listed.iter().map(|l| l.id.len()).max().unwrap_or(0);
+    let mut lines = Vec::new();
+    for l in listed {
+        let mut names = vec![l.source.to_string()];
+        names.extend(l.key.filter(|k| *k != l.id).map(|k| format!("key {}", k)));
+        let languages: Vec<String> = l.languages.iter().map(|l| l.to_string()).collect();
+        lines.push(format!(
+            "{:<width$}  {:<8}  {:<12}  {:<3}  {} ({})",
+            l.id,
+            l.severity,
+            l.category,
+            if l.enabled { "on" } else { "off" },
+            languages.join(", "),
+            names.join(", "),
+            width = width
+        ));
+        lines.push(format!("{:<width$}  {}", "", l.description, width = width));
+    }
+    lines.join("\n")
+}
+
+pub fn run(args: &[String]) -> i32 {
+    let format = match parse_options(args) {
+        Ok(f) => f,
+        Err(e) => return usage_error(&e),
+    };
+
+    let config = Config::load_for(&std::env::current_dir().unwrap_or_default());
+    let custom = rules::custom_rules(&config);
+
+    let mut listed: Vec<Listed> = REGISTRY
+        .iter()
+        .map(|r| {
+            let setting = (r.setting)(&config.rules);
+            Listed {
+                enabled: setting.enabled,
+                ..Listed::new(r.meta, Some(r.key), setting.severity)
+            }
+        })
+        .collect();
+    listed.extend(custom.iter().map(|c| Listed::new(c.meta(), None, None)));
+
+    match format {
+        Format::Text => println!("{}", format_text(&listed)),
+        Format::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&listed).unwrap_or_default()
+        ),
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_format() {
+        assert_eq!(parse_options(&args(&["list"])), Ok(Format::Text));
+        assert_eq!(
+            parse_options(&args(&["list", "--format", "json"])),
+            Ok(Format::Json)
+        );
+        assert!(parse_options(&args(&["list", "--format", "yaml"])).is_err());
+        assert!(parse_options(&[]).is_err());
+    }
+
+    #[test]
+    fn lists_keys_and_configured_severity() {
+        let r = rules::lookup("syncIo").unwrap();
+        let listed = [Listed::new(r.meta, Some(r.key), Some(Severity::High))];
+        let text = format_text(&listed);
+        assert!(text.starts_with("sync-io  HIGH"));
+        assert!(text.contains("(guardrails, key syncIo)"));
+
+        let json = serde_json::to_value(&listed).unwrap();
+        assert_eq!(json[0]["key"], "syncIo");
+        assert_eq!(json[0]["category"], "performance");
+        assert_eq!(json[0]["languages"][1], "typescript");
+    }
+}
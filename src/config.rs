@@ -1,9 +1,41 @@
-use crate::rules::Severity;
-use serde::Deserialize;
+use crate::rules::{self, Severity, Violation};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
-#[derive(Debug, Clone, Deserialize)]
+/// Per-project config file, looked up from the checked file up to the repo root.
+const PROJECT_CONFIG_FILE: &str = ".guardrails.json";
+
+/// Rule id for writes to guardrails' own config and baseline files.
+pub const GUARDRAILS_CONFIG: &str = "guardrails-config";
+
+/// A finding if writing `path` would change what guardrails checks: a project
+/// `.guardrails.json`, the baseline or the user config. Its decision is at
+/// least `ask`, whatever the config says, so a person reviews the change.
+pub fn guardrails_file_write(path: &str) -> Option<Violation> {
+    let name = Path::new(path).file_name()?;
+    let protected = name == PROJECT_CONFIG_FILE
+        || name == crate::baseline::BASELINE_FILE
+        || Path::new(path) == Config::config_path();
+    protected.then(|| Violation {
+        rule: GUARDRAILS_CONFIG.to_string(),
+        severity: Severity::Medium,
+        failure: "Changes guardrails' own config or baseline. A person should review this edit."
+            .to_string(),
+        file: path.to_string(),
+        line: None,
+        column: None,
+        end_line: None,
+        end_column: None,
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct Config {
     #[serde(default = "default_true")]
     pub enabled: bool,
@@ -11,68 +43,444 @@ pub struct Config {
     pub rules: RulesConfig,
     #[serde(default)]
     pub severity: SeverityConfig,
+    /// Only block on violations the current operation introduced.
+    #[serde(rename = "onlyNewViolations", default = "default_true")]
+    pub only_new_violations: bool,
+    #[serde(default)]
+    pub output: OutputMode,
+    /// Most occurrences of one rule reported per file; 0 reports all.
+    #[serde(rename = "maxOccurrences", default = "default_max_occurrences")]
+    pub max_occurrences: usize,
+    #[serde(rename = "codeFrame", default)]
+    pub code_frame: CodeFrameConfig,
+    #[serde(default)]
+    pub bash: BashConfig,
+    #[serde(default)]
+    pub read: ReadConfig,
+    #[serde(default)]
+    pub architecture: ArchitectureConfig,
+    /// Send hook payloads to a background `guardrails serve` process.
+    #[serde(default = "default_true")]
+    pub daemon: bool,
+    /// `rules` and `severity` changes for files matching a glob, applied in order.
+    #[serde(default)]
+    pub overrides: Vec<Override>,
+    /// Project-specific regex rules, checked like the built-in line rules.
+    #[serde(rename = "customRules", default)]
+    pub custom_rules: Vec<CustomRule>,
+    #[serde(skip)]
+    pub(crate) scoped_severity: ScopedSeverity,
 }
 
-#[derive(Debug, Clone, Deserialize)]
-pub struct RulesConfig {
-    #[serde(rename = "sensitiveFile", default = "default_true")]
-    pub sensitive_file: bool,
-    #[serde(default = "default_true")]
-    pub architecture: bool,
-    #[serde(default = "default_true")]
-    pub naming: bool,
-    #[serde(default = "default_true")]
-    pub transaction: bool,
-    #[serde(default = "default_true")]
-    pub security: bool,
-    #[serde(rename = "cryptoWeak", default = "default_true")]
-    pub crypto_weak: bool,
-    #[serde(rename = "generatedFile", default = "default_true")]
-    pub generated_file: bool,
-    #[serde(rename = "testLocation", default = "default_true")]
-    pub test_location: bool,
-    #[serde(rename = "domAccess", default = "default_true")]
-    pub dom_access: bool,
-    #[serde(rename = "syncIo", default = "default_true")]
-    pub sync_io: bool,
-    #[serde(rename = "bundleSize", default = "default_true")]
-    pub bundle_size: bool,
-    #[serde(rename = "testAssertion", default = "default_true")]
-    pub test_assertion: bool,
-    #[serde(rename = "flakyTest", default = "default_true")]
-    pub flaky_test: bool,
-    #[serde(rename = "sensitiveLogging", default = "default_true")]
-    pub sensitive_logging: bool,
+/// Severity policies keyed by the indices of the overrides that matched a
+/// file, so `decision` merges each set of overrides once.
+#[derive(Debug, Default)]
+pub(crate) struct ScopedSeverity(Mutex<HashMap<Vec<usize>, Arc<SeverityConfig>>>);
+
+/// Starts empty: entries are derived from the cloned config.
+impl Clone for ScopedSeverity {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+/// Always equal: the entries are derived from `severity` and `overrides`.
+impl PartialEq for ScopedSeverity {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+/// Path-scoped config, like ESLint `overrides`. Globs without a leading `/`
+/// match at any depth, so `packages/cli/**` also matches `/repo/packages/cli/a.ts`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Override {
+    pub files: Vec<String>,
+    #[serde(default)]
+    pub excludes: Vec<String>,
+    /// Partial `rules` object merged over the base rules.
+    #[serde(default)]
+    pub rules: Map<String, Value>,
+    /// Partial `severity` object merged over the base severity policy.
+    #[serde(default)]
+    pub severity: Map<String, Value>,
+    #[serde(skip)]
+    globs: CompiledGlobs,
+}
+
+/// `files` and `excludes` of an override, compiled once and kept with it.
+#[derive(Debug, Clone, Default)]
+struct CompiledGlobs(OnceCell<(GlobSet, GlobSet)>);
+
+/// Always equal: the globs are derived from `files` and `excludes`.
+impl PartialEq for CompiledGlobs {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+/// A regex rule declared in config. `requires`/`unless` patterns are matched
+/// anywhere in the file and gate the whole rule, like naming's `additional_check`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CustomRule {
+    pub id: String,
+    /// Globs as in `overrides`; JS/TS files when empty.
+    #[serde(default)]
+    pub files: Vec<String>,
+    pub patterns: Vec<String>,
+    #[serde(default)]
+    pub requires: Vec<String>,
+    #[serde(default)]
+    pub unless: Vec<String>,
+    #[serde(default = "default_custom_severity")]
+    pub severity: Severity,
+    pub fix: String,
+}
+
+fn default_max_occurrences() -> usize {
+    5
+}
+
+fn default_custom_severity() -> Severity {
+    Severity::Medium
+}
+
+pub(crate) fn glob_set(patterns: &[String]) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let anchored = if pattern.starts_with('/') || pattern.starts_with("**/") {
+            pattern.clone()
+        } else {
+            format!("**/{}", pattern)
+        };
+        match GlobBuilder::new(&anchored).literal_separator(true).build() {
+            Ok(glob) => {
+                builder.add(glob);
+            }
+            Err(e) => eprintln!("guardrails: warning: invalid glob {:?}: {}", pattern, e),
+        }
+    }
+    builder.build().unwrap_or_else(|_| GlobSet::empty())
+}
+
+impl Override {
+    fn globs(&self) -> &(GlobSet, GlobSet) {
+        self.globs
+            .0
+            .get_or_init(|| (glob_set(&self.files), glob_set(&self.excludes)))
+    }
+
+    pub fn matches(&self, file_path: &str) -> bool {
+        let (files, excludes) = self.globs();
+        files.is_match(file_path) && !excludes.is_match(file_path)
+    }
+}
+
+/// Settings for Bash tool command checks.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct BashConfig {
+    /// Branches that must not be force-pushed.
+    #[serde(rename = "protectedBranches", default = "default_protected_branches")]
+    pub protected_branches: Vec<String>,
+}
+
+fn default_protected_branches() -> Vec<String> {
+    vec!["main".to_string(), "master".to_string()]
+}
+
+/// Project layers for the architecture rule. Without layers the built-in
+/// utils/services/components checks apply.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct ArchitectureConfig {
+    #[serde(default)]
+    pub layers: Vec<Layer>,
+}
+
+/// A layer: files matching `files` (globs, as in `overrides`) may import
+/// their own layer and the layers named in `allow`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Layer {
+    pub name: String,
+    pub files: Vec<String>,
+    #[serde(default)]
+    pub allow: Vec<String>,
+}
+
+/// Settings for Read/Grep/Glob access to secret files.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ReadConfig {
+    #[serde(default = "default_read_severity")]
+    pub severity: Severity,
+    /// Extra path regexes to deny, on top of the sensitive file patterns.
+    #[serde(default)]
+    pub deny: Vec<String>,
+    /// Path regexes that are always readable, e.g. committed templates.
+    #[serde(default = "default_read_allow")]
+    pub allow: Vec<String>,
+}
+
+fn default_read_severity() -> Severity {
+    Severity::Critical
+}
+
+fn default_read_allow() -> Vec<String> {
+    vec![r"\.env\.(example|sample|template)$".to_string()]
+}
+
+impl Default for ReadConfig {
+    fn default() -> Self {
+        Self {
+            severity: default_read_severity(),
+            deny: Vec::new(),
+            allow: default_read_allow(),
+        }
+    }
+}
+
+impl Default for BashConfig {
+    fn default() -> Self {
+        Self {
+            protected_branches: default_protected_branches(),
+        }
+    }
+}
+
+/// Code excerpts under each finding in the blocking report.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CodeFrameConfig {
     #[serde(default = "default_true")]
-    pub biome: bool,
+    pub enabled: bool,
+    /// Characters of code shown per line; longer lines are cut around the finding.
+    #[serde(rename = "maxWidth", default = "default_frame_width")]
+    pub max_width: usize,
+}
+
+fn default_frame_width() -> usize {
+    100
 }
 
-impl Default for RulesConfig {
+impl Default for CodeFrameConfig {
     fn default() -> Self {
         Self {
-            sensitive_file: true,
-            architecture: true,
-            naming: true,
-            transaction: true,
-            security: true,
-            crypto_weak: true,
-            generated_file: true,
-            test_location: true,
-            dom_access: true,
-            sync_io: true,
-            bundle_size: true,
-            test_assertion: true,
-            flaky_test: true,
-            sensitive_logging: true,
-            biome: true,
-        }
-    }
-}
-
-#[derive(Debug, Clone, Deserialize)]
+            enabled: true,
+            max_width: default_frame_width(),
+        }
+    }
+}
+
+/// How results are reported back to Claude Code.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputMode {
+    /// Human-readable stderr, exit code 2 to block.
+    #[default]
+    Text,
+    /// Hook JSON protocol on stdout, exit code 0.
+    Json,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RulesConfig {
+    #[serde(rename = "sensitiveFile", default)]
+    pub sensitive_file: RuleSetting,
+    #[serde(default)]
+    pub architecture: RuleSetting,
+    #[serde(default)]
+    pub naming: RuleSetting,
+    #[serde(default)]
+    pub transaction: RuleSetting,
+    #[serde(default)]
+    pub security: RuleSetting,
+    #[serde(rename = "cryptoWeak", default)]
+    pub crypto_weak: RuleSetting,
+    #[serde(rename = "generatedFile", default)]
+    pub generated_file: RuleSetting,
+    #[serde(rename = "testLocation", default)]
+    pub test_location: RuleSetting,
+    #[serde(rename = "domAccess", default)]
+    pub dom_access: RuleSetting,
+    #[serde(rename = "syncIo", default)]
+    pub sync_io: RuleSetting,
+    #[serde(rename = "bundleSize", default)]
+    pub bundle_size: RuleSetting,
+    #[serde(rename = "circularImport", default)]
+    pub circular_import: RuleSetting,
+    #[serde(rename = "testAssertion", default)]
+    pub test_assertion: RuleSetting,
+    #[serde(rename = "flakyTest", default)]
+    pub flaky_test: RuleSetting,
+    #[serde(rename = "sensitiveLogging", default)]
+    pub sensitive_logging: RuleSetting,
+    #[serde(rename = "hardcodedSecret", default)]
+    pub hardcoded_secret: RuleSetting,
+    #[serde(default)]
+    pub biome: RuleSetting,
+    #[serde(default)]
+    pub bash: RuleSetting,
+    #[serde(rename = "sensitiveRead", default)]
+    pub sensitive_read: RuleSetting,
+}
+
+/// Per-rule settings. Accepts `true`/`false`, a severity or `"off"`, or
+/// `{ "enabled": true, "severity": "low", "patterns": { "<id>": "off" } }`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "RawRuleSetting")]
+pub struct RuleSetting {
+    pub enabled: bool,
+    /// Replaces the severity the rule reports with.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity: Option<Severity>,
+    /// Settings for individual patterns of multi-pattern rules (`security`, `cryptoWeak`).
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub patterns: HashMap<String, RuleSetting>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawRuleSetting {
+    Enabled(bool),
+    Level(String),
+    Full {
+        #[serde(default = "default_true")]
+        enabled: bool,
+        #[serde(default)]
+        severity: Option<Severity>,
+        #[serde(default)]
+        patterns: HashMap<String, RuleSetting>,
+    },
+}
+
+impl TryFrom<RawRuleSetting> for RuleSetting {
+    type Error = String;
+
+    fn try_from(raw: RawRuleSetting) -> Result<Self, String> {
+        Ok(match raw {
+            RawRuleSetting::Enabled(enabled) => Self {
+                enabled,
+                ..Self::default()
+            },
+            RawRuleSetting::Level(level) if level == "off" => Self {
+                enabled: false,
+                ..Self::default()
+            },
+            RawRuleSetting::Level(level) => Self {
+                severity: Some(
+                    serde_json::from_value(Value::String(level.clone()))
+                        .map_err(|_| format!("unknown severity {:?}", level))?,
+                ),
+                ..Self::default()
+            },
+            RawRuleSetting::Full {
+                enabled,
+                severity,
+                patterns,
+            } => Self {
+                enabled,
+                severity,
+                patterns,
+            },
+        })
+    }
+}
+
+impl Default for RuleSetting {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            severity: None,
+            patterns: HashMap::new(),
+        }
+    }
+}
+
+impl RuleSetting {
+    /// Apply the configured severity to violations of this rule.
+    pub fn apply(&self, violations: &mut [Violation]) {
+        if let Some(severity) = self.severity {
+            for v in violations {
+                v.severity = severity;
+            }
+        }
+    }
+
+    /// Setting for one pattern of a multi-pattern rule, or None if it is turned off.
+    /// Falls back to the rule's severity, then to `default`.
+    pub fn pattern_severity(&self, id: &str, default: Severity) -> Option<Severity> {
+        match self.patterns.get(id) {
+            Some(p) if !p.enabled => None,
+            Some(p) => Some(p.severity.or(self.severity).unwrap_or(default)),
+            None => Some(self.severity.unwrap_or(default)),
+        }
+    }
+
+    /// Warn about pattern ids the rule does not define.
+    pub fn warn_unknown_patterns(&self, rule: &str, known: &[&str]) {
+        for id in self.patterns.keys() {
+            if !known.contains(&id.as_str()) {
+                eprintln!(
+                    "guardrails: warning: unknown {} pattern {:?} (known: {})",
+                    rule,
+                    id,
+                    known.join(", ")
+                );
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SeverityConfig {
     #[serde(rename = "blockOn", default = "default_block_on")]
     pub block_on: Vec<Severity>,
+    #[serde(rename = "askOn", default)]
+    pub ask_on: Vec<Severity>,
+    /// Per-rule decision, keyed by violation rule id (e.g. `transaction-boundary`,
+    /// `biome/lint/suspicious/noExplicitAny`) or config key (`transaction`).
+    /// Takes precedence over severity.
+    #[serde(default)]
+    pub rules: HashMap<String, Decision>,
+}
+
+/// What happens to the tool call when a violation is found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Decision {
+    /// Report as a warning and let the operation proceed.
+    Allow,
+    /// Ask the user to approve the operation.
+    Ask,
+    /// Block the operation.
+    Deny,
+}
+
+impl SeverityConfig {
+    pub fn decision(&self, violation: &Violation) -> Decision {
+        if violation.rule == GUARDRAILS_CONFIG {
+            return match self.rules.get(GUARDRAILS_CONFIG) {
+                Some(Decision::Deny) => Decision::Deny,
+                _ => Decision::Ask,
+            };
+        }
+        if violation.severity == Severity::Info {
+            return Decision::Allow;
+        }
+        let by_name = self.rules.get(&violation.rule).or_else(|| {
+            self.rules
+                .iter()
+                .find(|(name, _)| rules::rule_matches(name, &violation.rule))
+                .map(|(_, decision)| decision)
+        });
+        if let Some(&decision) = by_name {
+            return decision;
+        }
+        if self.block_on.contains(&violation.severity) {
+            Decision::Deny
+        } else if self.ask_on.contains(&violation.severity) {
+            Decision::Ask
+        } else {
+            Decision::Allow
+        }
+    }
 }
 
 fn default_true() -> bool {
@@ -87,6 +495,8 @@ impl Default for SeverityConfig {
     fn default() -> Self {
         Self {
             block_on: default_block_on(),
+            ask_on: Vec::new(),
+            rules: HashMap::new(),
         }
     }
 }
@@ -97,37 +507,169 @@ impl Default for Config {
             enabled: true,
             rules: RulesConfig::default(),
             severity: SeverityConfig::default(),
+            only_new_violations: true,
+            output: OutputMode::default(),
+            max_occurrences: default_max_occurrences(),
+            code_frame: CodeFrameConfig::default(),
+            bash: BashConfig::default(),
+            read: ReadConfig::default(),
+            architecture: ArchitectureConfig::default(),
+            daemon: true,
+            overrides: Vec::new(),
+            custom_rules: Vec::new(),
+            scoped_severity: ScopedSeverity::default(),
         }
     }
 }
 
 impl Config {
-    pub fn load() -> Self {
-        let config_path = Self::config_path();
+    /// User config with the project `.guardrails.json` files for `path` merged on top.
+    pub fn load_for(path: &Path) -> Self {
+        Self::resolve(path).config
+    }
+
+    /// Effective config for `path` and the files it was merged from.
+    pub fn resolve(path: &Path) -> ResolvedConfig {
+        let mut layers: Vec<ConfigLayer> = Self::user_layer().into_iter().collect();
+        layers.extend(
+            Self::project_files(path)
+                .into_iter()
+                .filter_map(|p| read_layer(&p)),
+        );
+        ResolvedConfig {
+            config: Self::from_layers(&layers),
+            layers,
+        }
+    }
+
+    /// Project `.guardrails.json` files that apply to `path`, repo root first.
+    pub fn project_files(path: &Path) -> Vec<PathBuf> {
+        let path = std::env::current_dir()
+            .map(|cwd| cwd.join(path))
+            .unwrap_or_else(|_| path.to_path_buf());
+        project_config_paths(&path)
+    }
 
-        match fs::read_to_string(&config_path) {
-            Ok(content) => match serde_json::from_str::<Config>(&content) {
-                Ok(config) => config,
+    /// Turn biome off everywhere, including overrides that enable it.
+    pub fn disable_biome(&mut self) {
+        self.rules.biome.enabled = false;
+        for o in &mut self.overrides {
+            o.rules.remove("biome");
+        }
+    }
+
+    /// Indices of the overrides that apply to `file_path`.
+    pub fn matching_overrides(&self, file_path: &str) -> Vec<usize> {
+        self.overrides
+            .iter()
+            .enumerate()
+            .filter(|(_, o)| o.matches(file_path))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// This config with the given overrides applied in order.
+    pub fn with_overrides(&self, indices: &[usize]) -> Config {
+        let mut rules = serde_json::to_value(&self.rules).unwrap_or_default();
+        for o in indices.iter().filter_map(|&i| self.overrides.get(i)) {
+            merge(&mut rules, &Value::Object(o.rules.clone()));
+        }
+
+        let mut config = self.clone();
+        match serde_json::from_value(rules) {
+            Ok(rules) => config.rules = rules,
+            Err(e) => eprintln!("guardrails: warning: invalid override rules: {}", e),
+        }
+        config.severity = self.severity_for(indices).as_ref().clone();
+        config
+    }
+
+    /// `severity` with the given overrides applied in order, merged once per set.
+    fn severity_for(&self, indices: &[usize]) -> Arc<SeverityConfig> {
+        let mut scoped = self
+            .scoped_severity
+            .0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let scoped = scoped.entry(indices.to_vec()).or_insert_with(|| {
+            let mut severity = serde_json::to_value(&self.severity).unwrap_or_default();
+            for o in indices.iter().filter_map(|&i| self.overrides.get(i)) {
+                merge(&mut severity, &Value::Object(o.severity.clone()));
+            }
+            match serde_json::from_value(severity) {
+                Ok(severity) => Arc::new(severity),
                 Err(e) => {
-                    eprintln!(
-                        "guardrails: warning: invalid config at {:?}: {}",
-                        config_path, e
-                    );
-                    eprintln!("guardrails: using default configuration");
-                    Config::default()
+                    eprintln!("guardrails: warning: invalid override severity: {}", e);
+                    Arc::new(self.severity.clone())
                 }
-            },
-            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Config::default(),
+            }
+        });
+        Arc::clone(scoped)
+    }
+
+    /// Decision for a violation, honouring overrides that match its file.
+    pub fn decision(&self, violation: &Violation) -> Decision {
+        let matched = self.matching_overrides(&violation.file);
+        if matched.is_empty() {
+            self.severity.decision(violation)
+        } else {
+            self.severity_for(&matched).decision(violation)
+        }
+    }
+
+    fn user_layer() -> Option<ConfigLayer> {
+        read_layer(&Self::config_path())
+    }
+
+    fn from_layers(layers: &[ConfigLayer]) -> Self {
+        let mut merged = Value::Object(Default::default());
+        for layer in layers {
+            let mut value = layer.value.clone();
+            normalize_rule_keys(&mut value, &layer.path);
+            merge(&mut merged, &value);
+        }
+        match serde_json::from_value::<Config>(merged) {
+            Ok(config) => {
+                config.warn_unknown_decisions();
+                // Compile override globs now, reporting invalid ones once.
+                for o in &config.overrides {
+                    o.globs();
+                }
+                config
+            }
             Err(e) => {
+                let paths: Vec<String> = layers
+                    .iter()
+                    .map(|l| l.path.display().to_string())
+                    .collect();
                 eprintln!(
-                    "guardrails: warning: cannot read config {:?}: {}",
-                    config_path, e
+                    "guardrails: warning: invalid config from {}: {}",
+                    paths.join(", "),
+                    e
                 );
+                eprintln!("guardrails: using default configuration");
                 Config::default()
             }
         }
     }
 
+    /// Warn about `severity.rules` names that match no rule.
+    fn warn_unknown_decisions(&self) {
+        let overrides = self
+            .overrides
+            .iter()
+            .filter_map(|o| o.severity.get("rules")?.as_object())
+            .flat_map(|rules| rules.keys());
+        for name in self.severity.rules.keys().chain(overrides) {
+            if !rules::is_known_rule(name) && !self.custom_rules.iter().any(|c| c.id == *name) {
+                eprintln!(
+                    "guardrails: warning: unknown rule {:?} in severity.rules",
+                    name
+                );
+            }
+        }
+    }
+
     fn config_path() -> PathBuf {
         Self::config_search_paths(std::env::current_exe().ok().as_deref())
             .into_iter()
@@ -158,6 +700,122 @@ impl Config {
     }
 }
 
+/// One config file that contributed to the effective config.
+pub struct ConfigLayer {
+    pub path: PathBuf,
+    pub value: Value,
+}
+
+pub struct ResolvedConfig {
+    pub config: Config,
+    /// Lowest precedence first: user config, then project files from the repo root down.
+    pub layers: Vec<ConfigLayer>,
+}
+
+fn read_layer(path: &Path) -> Option<ConfigLayer> {
+    let content = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            eprintln!("guardrails: warning: cannot read config {:?}: {}", path, e);
+            return None;
+        }
+    };
+    match serde_json::from_str::<Value>(&content) {
+        Ok(value @ Value::Object(_)) => Some(ConfigLayer {
+            path: path.to_path_buf(),
+            value,
+        }),
+        Ok(_) => {
+            eprintln!(
+                "guardrails: warning: ignoring config {:?}: not an object",
+                path
+            );
+            None
+        }
+        Err(e) => {
+            eprintln!(
+                "guardrails: warning: ignoring invalid config {:?}: {}",
+                path, e
+            );
+            None
+        }
+    }
+}
+
+/// Project config files that apply to `path`, outermost first. The walk starts
+/// at `path` (or its directory) and stops at the first directory with `.git`.
+fn project_config_paths(path: &Path) -> Vec<PathBuf> {
+    let start = if path.is_dir() {
+        path
+    } else {
+        path.parent().unwrap_or(path)
+    };
+
+    let mut paths = Vec::new();
+    for dir in start.ancestors() {
+        let candidate = dir.join(PROJECT_CONFIG_FILE);
+        if candidate.is_file() {
+            paths.push(candidate);
+        }
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+    paths.reverse();
+    paths
+}
+
+/// Rewrite rule ids under `rules` (`sync-io`) to config keys (`syncIo`), in
+/// the layer and its overrides, and warn about names no rule has.
+fn normalize_rule_keys(layer: &mut Value, path: &Path) {
+    if let Some(rules) = layer.get_mut("rules").and_then(Value::as_object_mut) {
+        normalize_section(rules, path);
+    }
+    if let Some(overrides) = layer.get_mut("overrides").and_then(Value::as_array_mut) {
+        for o in overrides {
+            if let Some(rules) = o.get_mut("rules").and_then(Value::as_object_mut) {
+                normalize_section(rules, path);
+            }
+        }
+    }
+}
+
+fn normalize_section(rules: &mut Map<String, Value>, path: &Path) {
+    for name in rules.keys().cloned().collect::<Vec<_>>() {
+        match rules::lookup(&name) {
+            Some(r) if r.key != name => {
+                if let Some(value) = rules.remove(&name) {
+                    rules.insert(r.key.to_string(), value);
+                }
+            }
+            Some(_) => {}
+            None => eprintln!(
+                "guardrails: warning: unknown rule {:?} in {}",
+                name,
+                path.display()
+            ),
+        }
+    }
+}
+
+/// Deep-merge `overlay` into `base`: objects merge by key, anything else replaces.
+fn merge(base: &mut Value, overlay: &Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay.clone(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -179,8 +837,15 @@ mod tests {
     fn default_config_all_rules_enabled() {
         let config = Config::default();
         assert!(config.enabled);
-        assert!(config.rules.sensitive_file);
-        assert!(config.rules.biome);
+        assert!(config.rules.sensitive_file.enabled);
+        assert!(config.rules.biome.enabled);
+    }
+
+    #[test]
+    fn output_mode_parses() {
+        let config: Config = serde_json::from_str(r#"{"output": "json"}"#).unwrap();
+        assert_eq!(config.output, OutputMode::Json);
+        assert_eq!(Config::default().output, OutputMode::Text);
     }
 
     #[test]
@@ -190,4 +855,217 @@ mod tests {
         assert!(config.severity.block_on.contains(&Severity::High));
         assert!(!config.severity.block_on.contains(&Severity::Medium));
     }
+
+    fn violation(rule: &str, severity: Severity) -> Violation {
+        Violation {
+            rule: rule.to_string(),
+            severity,
+            failure: String::new(),
+            file: "/src/a.ts".to_string(),
+            line: None,
+            column: None,
+            end_line: None,
+            end_column: None,
+        }
+    }
+
+    #[test]
+    fn decision_by_severity() {
+        let config: Config = serde_json::from_str(
+            r#"{"severity": {"blockOn": ["critical", "high"], "askOn": ["medium"]}}"#,
+        )
+        .unwrap();
+        let decide = |s| config.severity.decision(&violation("x", s));
+        assert_eq!(decide(Severity::High), Decision::Deny);
+        assert_eq!(decide(Severity::Medium), Decision::Ask);
+        assert_eq!(decide(Severity::Low), Decision::Allow);
+    }
+
+    #[test]
+    fn guardrails_files_need_at_least_ask() {
+        for path in ["/repo/.guardrails.json", "/repo/.guardrails-baseline.json"] {
+            let v = guardrails_file_write(path).unwrap();
+            let config: Config = serde_json::from_str(
+                r#"{"severity": {"blockOn": [], "rules": {"guardrails-config": "allow"}}}"#,
+            )
+            .unwrap();
+            assert_eq!(config.severity.decision(&v), Decision::Ask);
+            let config: Config =
+                serde_json::from_str(r#"{"severity": {"rules": {"guardrails-config": "deny"}}}"#)
+                    .unwrap();
+            assert_eq!(config.severity.decision(&v), Decision::Deny);
+        }
+        assert!(guardrails_file_write("/repo/src/guardrails.json").is_none());
+    }
+
+    #[test]
+    fn decision_rule_override_wins() {
+        let config: Config = serde_json::from_str(
+            r#"{"severity": {"rules": {"dom-access": "ask", "security": "allow"}}}"#,
+        )
+        .unwrap();
+        let decide = |r, s| config.severity.decision(&violation(r, s));
+        assert_eq!(decide("dom-access", Severity::Medium), Decision::Ask);
+        assert_eq!(decide("security", Severity::High), Decision::Allow);
+        assert_eq!(decide("sync-io", Severity::High), Decision::Deny);
+    }
+
+    #[test]
+    fn merge_is_deep_and_replaces_arrays() {
+        let mut base = serde_json::json!({"rules": {"naming": false, "syncIo": true}, "severity": {"blockOn": ["critical", "high"]}});
+        merge(
+            &mut base,
+            &serde_json::json!({"rules": {"syncIo": false}, "severity": {"blockOn": ["critical"]}}),
+        );
+        assert_eq!(
+            base,
+            serde_json::json!({"rules": {"naming": false, "syncIo": false}, "severity": {"blockOn": ["critical"]}})
+        );
+    }
+
+    #[test]
+    fn project_configs_stop_at_repo_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("repo");
+        let package = root.join("packages/web");
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::create_dir_all(&package).unwrap();
+        fs::write(dir.path().join(PROJECT_CONFIG_FILE), "{}").unwrap();
+        fs::write(root.join(PROJECT_CONFIG_FILE), "{}").unwrap();
+        fs::write(package.join(PROJECT_CONFIG_FILE), "{}").unwrap();
+
+        let paths = project_config_paths(&package.join("src/new-file.ts"));
+        assert_eq!(
+            paths,
+            [
+                root.join(PROJECT_CONFIG_FILE),
+                package.join(PROJECT_CONFIG_FILE)
+            ]
+        );
+    }
+
+    #[test]
+    fn nearest_project_config_wins() {
+        let dir = tempfile::tempdir().unwrap();
+        let package = dir.path().join("packages/web");
+        fs::create_dir_all(dir.path().join(".git")).unwrap();
+        fs::create_dir_all(&package).unwrap();
+        fs::write(
+            dir.path().join(PROJECT_CONFIG_FILE),
+            r#"{"rules": {"syncIo": false, "naming": false}}"#,
+        )
+        .unwrap();
+        fs::write(
+            package.join(PROJECT_CONFIG_FILE),
+            r#"{"rules": {"naming": true}}"#,
+        )
+        .unwrap();
+
+        let resolved = Config::resolve(&package.join("a.ts"));
+        assert!(!resolved.config.rules.sync_io.enabled);
+        assert!(resolved.config.rules.naming.enabled);
+        assert!(resolved.layers.len() >= 2);
+    }
+
+    #[test]
+    fn rule_ids_work_as_config_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join(".git")).unwrap();
+        fs::write(
+            dir.path().join(PROJECT_CONFIG_FILE),
+            r#"{"rules": {"sync-io": false, "naming-convention": "low"},
+                "overrides": [{"files": ["scripts/**"], "rules": {"dom-access": false}}],
+                "severity": {"rules": {"transaction": "ask"}}}"#,
+        )
+        .unwrap();
+
+        let config = Config::load_for(&dir.path().join("a.ts"));
+        assert!(!config.rules.sync_io.enabled);
+        assert_eq!(config.rules.naming.severity, Some(Severity::Low));
+        assert!(config.overrides[0].rules.contains_key("domAccess"));
+        assert_eq!(
+            config.decision(&violation("transaction-boundary", Severity::High)),
+            Decision::Ask
+        );
+    }
+
+    fn monorepo() -> Config {
+        serde_json::from_str(
+            r#"{"overrides": [
+                {"files": ["packages/cli/**"], "rules": {"syncIo": false}},
+                {"files": ["packages/server/**"], "excludes": ["**/*.test.ts"], "rules": {"domAccess": false},
+                 "severity": {"rules": {"security": "ask"}}}
+            ]}"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn overrides_match_at_any_depth() {
+        let config = monorepo();
+        assert_eq!(
+            config.matching_overrides("/repo/packages/cli/src/a.ts"),
+            [0]
+        );
+        assert_eq!(config.matching_overrides("packages/server/a.ts"), [1]);
+        assert!(config
+            .matching_overrides("/repo/packages/server/a.test.ts")
+            .is_empty());
+        assert!(config.matching_overrides("/repo/apps/web/a.ts").is_empty());
+
+        // Compiled on first use and kept by clones, e.g. `with_overrides`.
+        let scoped = config.with_overrides(&[0]);
+        assert!(scoped.overrides.iter().all(|o| o.globs.0.get().is_some()));
+    }
+
+    #[test]
+    fn overrides_merge_partial_rules_and_severity() {
+        let config = monorepo();
+        let cli = config.with_overrides(&[0]);
+        assert!(!cli.rules.sync_io.enabled);
+        assert!(cli.rules.dom_access.enabled);
+
+        let mut v = violation("security", Severity::High);
+        assert_eq!(config.decision(&v), Decision::Deny);
+        v.file = "/repo/packages/server/a.ts".to_string();
+        assert_eq!(config.decision(&v), Decision::Ask);
+    }
+
+    #[test]
+    fn scoped_severity_is_merged_once_per_override_set() {
+        let config = monorepo();
+        let mut v = violation("security", Severity::High);
+        for file in ["a.ts", "b.ts", "c.ts"] {
+            v.file = format!("/repo/packages/server/{}", file);
+            assert_eq!(config.decision(&v), Decision::Ask);
+        }
+        assert_eq!(config.scoped_severity.0.lock().unwrap().len(), 1);
+        assert!(config.clone().scoped_severity.0.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn rule_setting_forms() {
+        let config: Config = serde_json::from_str(
+            r#"{"rules": {"naming": {"enabled": true, "severity": "low"}, "syncIo": false, "flakyTest": "high", "domAccess": "off"}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.rules.naming.severity, Some(Severity::Low));
+        assert!(!config.rules.sync_io.enabled);
+        assert_eq!(config.rules.flaky_test.severity, Some(Severity::High));
+        assert!(!config.rules.dom_access.enabled);
+        assert!(config.rules.security.enabled);
+        assert!(serde_json::from_str::<Config>(r#"{"rules": {"naming": "loud"}}"#).is_err());
+    }
+
+    #[test]
+    fn info_never_blocks() {
+        let config: Config = serde_json::from_str(
+            r#"{"severity": {"blockOn": ["info"], "rules": {"naming-convention": "deny"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.decision(&violation("naming-convention", Severity::Info)),
+            Decision::Allow
+        );
+    }
 }
@@ -0,0 +1,305 @@
+//! `guardrails serve`: a background process that answers hook requests over a
+//! Unix socket, so rules, config and biome stay warm between tool calls.
+//!
+//! The hook sends one JSON line (`Request`) and reads one back (`Response`).
+//! Any failure makes the hook fall back to checking in-process.
+
+use crate::biome;
+use crate::hook::{self, Hook, Outcome};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The daemon exits after this long without requests.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Distinct configs (e.g. one per project) kept warm at a time.
+const MAX_HOOKS: usize = 8;
+
+/// How long the hook waits for the daemon before checking in-process. Well
+/// under the hook's 1s budget, since the daemon serves one connection at a
+/// time and parallel tool calls queue behind each other.
+const CLIENT_TIMEOUT: Duration = Duration::from_millis(400);
+
+/// How long the daemon waits on a client; longer than any check should take.
+const SERVER_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Serialize, Deserialize)]
+struct Request {
+    version: String,
+    cwd: PathBuf,
+    input: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Response {
+    version: String,
+    /// None when the request could not be handled, e.g. a version mismatch.
+    outcome: Option<Outcome>,
+}
+
+/// `$GUARDRAILS_SOCKET`, else `$XDG_RUNTIME_DIR/guardrails.sock`, else the user's cache dir.
+pub fn socket_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("GUARDRAILS_SOCKET") {
+        return Some(PathBuf::from(path));
+    }
+    if let Some(dir) = std::env::var_os("XDG_RUNTIME_DIR") {
+        return Some(PathBuf::from(dir).join("guardrails.sock"));
+    }
+    let cache = std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".cache")))?;
+    Some(cache.join("guardrails").join("guardrails.sock"))
+}
+
+/// Ask a running daemon to check `input`. Starts one for next time if none answers.
+pub fn request(input: &str) -> Option<Outcome> {
+    let socket = socket_path()?;
+    let Ok(stream) = UnixStream::connect(&socket) else {
+        spawn();
+        return None;
+    };
+
+    let request = Request {
+        version: VERSION.to_string(),
+        cwd: std::env::current_dir().ok()?,
+        input: input.to_string(),
+    };
+    match exchange(stream, &request) {
+        Ok(response) if response.version == VERSION => response.outcome,
+        Ok(_) => None,
+        Err(e) => {
+            eprintln!("guardrails: daemon: {}", e);
+            None
+        }
+    }
+}
+
+fn exchange(mut stream: UnixStream, request: &Request) -> std::io::Result<Response> {
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+    writeln!(stream, "{}", serde_json::to_string(request)?)?;
+
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line)?;
+    Ok(serde_json::from_str(&line)?)
+}
+
+/// Start `guardrails serve` detached from the hook's process group.
+fn spawn() {
+    let Ok(exe) = std::env::current_exe() else {
+        return;
+    };
+    if let Err(e) = Command::new(exe)
+        .arg("serve")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .process_group(0)
+        .spawn()
+    {
+        eprintln!("guardrails: failed to start daemon: {}", e);
+    }
+}
+
+/// Hooks for recently used configs, reused while the config is unchanged.
+struct State {
+    hooks: Vec<Hook>,
+    biome_server: bool,
+}
+
+impl State {
+    fn handle(&mut self, request: Request) -> Option<Outcome> {
+        // Config discovery, relative paths and git all resolve against the cwd.
+        std::env::set_current_dir(&request.cwd).ok()?;
+        let input = match hook::parse_input(&request.input) {
+            Ok(input) => input,
+            Err(outcome) => return Some(outcome),
+        };
+        let config = hook::config_for(&input);
+
+        let index = match self.hooks.iter().position(|h| *h.config() == config) {
+            Some(index) => index,
+            None => {
+                if self.hooks.len() == MAX_HOOKS {
+                    self.hooks.remove(0);
+                }
+                let hook = Hook::new(config);
+                self.hooks.push(if self.biome_server {
+                    hook.with_biome_server()
+                } else {
+                    hook
+                });
+                self.hooks.len() - 1
+            }
+        };
+
+        Some(self.hooks[index].check(&input))
+    }
+
+    /// Serve one connection. Returns false if the daemon should exit.
+    fn serve_connection(&mut self, stream: UnixStream) -> std::io::Result<bool> {
+        stream.set_read_timeout(Some(SERVER_TIMEOUT))?;
+        stream.set_write_timeout(Some(SERVER_TIMEOUT))?;
+        let mut reader = BufReader::new(&stream);
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let request: Request = serde_json::from_str(&line)?;
+
+        // A different binary is installed: let the hook fall back and the next
+        // call start a matching daemon.
+        let current = request.version == VERSION;
+        let outcome = if current { self.handle(request) } else { None };
+
+        let response = Response {
+            version: VERSION.to_string(),
+            outcome,
+        };
+        writeln!(&stream, "{}", serde_json::to_string(&response)?)?;
+        Ok(current)
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn shutdown(socket: &Path, biome_server: bool) -> ! {
+    let _ = std::fs::remove_file(socket);
+    if biome_server {
+        biome::stop_server();
+    }
+    std::process::exit(0);
+}
+
+/// Bind the socket, replacing a stale one. None if another daemon is live.
+fn bind(socket: &Path) -> std::io::Result<Option<UnixListener>> {
+    if let Some(dir) = socket.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    match UnixListener::bind(socket) {
+        Ok(listener) => Ok(Some(listener)),
+        Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => {
+            if UnixStream::connect(socket).is_ok() {
+                return Ok(None);
+            }
+            std::fs::remove_file(socket)?;
+            UnixListener::bind(socket).map(Some)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+pub fn serve() -> i32 {
+    let Some(socket) = socket_path() else {
+        eprintln!("guardrails: cannot determine socket path (set GUARDRAILS_SOCKET)");
+        return 1;
+    };
+
+    let listener = match bind(&socket) {
+        Ok(Some(listener)) => listener,
+        Ok(None) => {
+            eprintln!("guardrails: daemon already running on {}", socket.display());
+            return 0;
+        }
+        Err(e) => {
+            eprintln!("guardrails: cannot listen on {}: {}", socket.display(), e);
+            return 1;
+        }
+    };
+    if let Err(e) = std::fs::set_permissions(&socket, std::fs::Permissions::from_mode(0o600)) {
+        eprintln!("guardrails: cannot restrict {}: {}", socket.display(), e);
+        return 1;
+    }
+
+    let biome_server = biome::is_available() && biome::start_server();
+    let mut state = State {
+        hooks: Vec::new(),
+        biome_server,
+    };
+
+    let last_request = Arc::new(AtomicU64::new(now_secs()));
+    {
+        let last_request = Arc::clone(&last_request);
+        let socket = socket.clone();
+        std::thread::spawn(move || loop {
+            std::thread::sleep(Duration::from_secs(60));
+            if now_secs().saturating_sub(last_request.load(Ordering::Relaxed))
+                >= IDLE_TIMEOUT.as_secs()
+            {
+                shutdown(&socket, biome_server);
+            }
+        });
+    }
+
+    eprintln!("guardrails: listening on {}", socket.display());
+    for stream in listener.incoming() {
+        last_request.store(now_secs(), Ordering::Relaxed);
+        let stream = match stream {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("guardrails: daemon: {}", e);
+                continue;
+            }
+        };
+        match state.serve_connection(stream) {
+            Ok(true) => {}
+            Ok(false) => shutdown(&socket, biome_server),
+            Err(e) => eprintln!("guardrails: daemon: {}", e),
+        }
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> State {
+        State {
+            hooks: Vec::new(),
+            biome_server: false,
+        }
+    }
+
+    fn round_trip(state: &mut State, version: &str) -> (Response, bool) {
+        let (client, server) = UnixStream::pair().unwrap();
+        let request = Request {
+            version: version.to_string(),
+            cwd: std::env::current_dir().unwrap(),
+            input: r#"{"tool_name":"Bash","tool_input":{"command":"rm -rf /"}}"#.to_string(),
+        };
+        let handle = std::thread::spawn(move || exchange(client, &request).unwrap());
+        let keep_running = state.serve_connection(server).unwrap();
+        (handle.join().unwrap(), keep_running)
+    }
+
+    #[test]
+    fn answers_requests_with_hook_outcome() {
+        let mut state = state();
+        let (response, keep_running) = round_trip(&mut state, VERSION);
+        assert!(keep_running);
+        assert_eq!(response.version, VERSION);
+        assert_eq!(response.outcome.unwrap().code, 2);
+        assert_eq!(state.hooks.len(), 1);
+    }
+
+    #[test]
+    fn version_mismatch_stops_daemon() {
+        let (response, keep_running) = round_trip(&mut state(), "0.0.0-other");
+        assert!(!keep_running);
+        assert!(response.outcome.is_none());
+    }
+}
@@ -0,0 +1,207 @@
+//! Line-independent violation identity, used to tell new violations from existing ones.
+//!
+//! A fingerprint is the rule id plus the whitespace-normalized matched text,
+//! or the whole offending line for findings without a column span, so it
+//! survives edits that only shift lines around or touch the rest of the line.
+
+use crate::rules::Violation;
+use std::collections::HashMap;
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Text between the violation's start and end columns, when it has them.
+fn span_text(v: &Violation, content: &str) -> Option<String> {
+    let (line, column) = (v.line?, v.column?);
+    let (end_line, end_column) = (v.end_line?, v.end_column?);
+    let lines: Vec<&str> = content
+        .lines()
+        .skip(line.checked_sub(1)? as usize)
+        .take(end_line.checked_sub(line)? as usize + 1)
+        .collect();
+    let last = lines.len().checked_sub(1)?;
+    let text: Vec<String> = lines
+        .iter()
+        .enumerate()
+        .map(|(i, l)| {
+            let start = if i == 0 { column as usize } else { 1 };
+            let end = if i == last {
+                (end_column as usize).saturating_sub(1)
+            } else {
+                usize::MAX
+            };
+            l.chars().take(end).skip(start.saturating_sub(1)).collect()
+        })
+        .collect();
+    Some(text.join("\n"))
+}
+
+/// Returns None for violations without a line (path-based rules), which describe
+/// the operation itself rather than existing content.
+pub fn fingerprint(v: &Violation, content: &str) -> Option<String> {
+    let line = v.line?;
+    let text = match span_text(v, content) {
+        Some(text) => text,
+        None => content
+            .lines()
+            .nth(line.checked_sub(1)? as usize)?
+            .to_string(),
+    };
+    Some(format!("{}\u{0}{}", v.rule, normalize(&text)))
+}
+
+/// Stable 64-bit FNV-1a hash of the normalized line text, as hex. Path-based
+/// violations hash the empty string. Used by the baseline file, so it must not
+/// change between releases.
+pub fn line_hash(v: &Violation, content: &str) -> String {
+    let text = v
+        .line
+        .and_then(|l| l.checked_sub(1))
+        .and_then(|l| content.lines().nth(l as usize))
+        .map(normalize)
+        .unwrap_or_default();
+    hash(&text)
+}
+
+/// 64-bit FNV-1a of `text` as 16 hex digits. Stable across releases and platforms.
+pub fn hash(text: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in text.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// Keep only violations in `after` that have no counterpart in `before`.
+/// Matching is by multiset, so adding a second identical line is still reported.
+pub fn new_violations(
+    before: &[Violation],
+    before_content: &str,
+    after: Vec<Violation>,
+    after_content: &str,
+) -> Vec<Violation> {
+    let mut existing: HashMap<String, usize> = HashMap::new();
+    for v in before {
+        if let Some(fp) = fingerprint(v, before_content) {
+            *existing.entry(fp).or_default() += 1;
+        }
+    }
+
+    after
+        .into_iter()
+        .filter(|v| {
+            let Some(fp) = fingerprint(v, after_content) else {
+                return true;
+            };
+            match existing.get_mut(&fp) {
+                Some(n) if *n > 0 => {
+                    *n -= 1;
+                    false
+                }
+                _ => true,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Severity;
+
+    fn violation(rule: &str, line: Option<u32>) -> Violation {
+        Violation {
+            rule: rule.to_string(),
+            severity: Severity::Medium,
+            failure: "fix".to_string(),
+            file: "/src/a.ts".to_string(),
+            line,
+            column: None,
+            end_line: None,
+            end_column: None,
+        }
+    }
+
+    #[test]
+    fn ignores_existing_violation_after_line_shift() {
+        let before = "readFileSync(a);\n";
+        let after = "import x from 'y';\n\n  readFileSync(a);\n";
+        let result = new_violations(
+            &[violation("sync-io", Some(1))],
+            before,
+            vec![violation("sync-io", Some(3))],
+            after,
+        );
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn spans_ignore_edits_elsewhere_on_the_line() {
+        let spanned = |line, column| Violation {
+            column: Some(column),
+            end_line: Some(line),
+            end_column: Some(column + 12),
+            ..violation("sync-io", Some(line))
+        };
+        let before = "const x = readFileSync(p);\n";
+        let after = "// read\nconst config = readFileSync(p);\n";
+        let result = new_violations(&[spanned(1, 11)], before, vec![spanned(2, 16)], after);
+        assert!(result.is_empty());
+
+        let after = "const x = readFileSync(p); const y = readFileSync(q);\n";
+        let result = new_violations(
+            &[spanned(1, 11)],
+            before,
+            vec![spanned(1, 11), spanned(1, 38)],
+            after,
+        );
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].column, Some(38));
+    }
+
+    #[test]
+    fn reports_added_duplicate() {
+        let before = "readFileSync(a);\n";
+        let after = "readFileSync(a);\nreadFileSync(a);\n";
+        let result = new_violations(
+            &[violation("sync-io", Some(1))],
+            before,
+            vec![violation("sync-io", Some(1)), violation("sync-io", Some(2))],
+            after,
+        );
+        assert_eq!(result.len(), 1);
+    }
+
+    #[test]
+    fn reports_different_rule_on_same_line() {
+        let content = "readFileSync(a);\n";
+        let result = new_violations(
+            &[violation("sync-io", Some(1))],
+            content,
+            vec![violation("security", Some(1))],
+            content,
+        );
+        assert_eq!(result.len(), 1);
+    }
+
+    #[test]
+    fn always_reports_path_violations() {
+        let result = new_violations(
+            &[violation("sensitive-file", None)],
+            "A=1",
+            vec![violation("sensitive-file", None)],
+            "A=2",
+        );
+        assert_eq!(result.len(), 1);
+    }
+
+    #[test]
+    fn line_hash_ignores_position_and_whitespace() {
+        let a = line_hash(&violation("sync-io", Some(1)), "  readFileSync(a);\n");
+        let b = line_hash(&violation("sync-io", Some(3)), "x\ny\nreadFileSync(a);");
+        assert_eq!(a, b);
+        assert_eq!(line_hash(&violation("x", None), "abc"), "cbf29ce484222325");
+    }
+}
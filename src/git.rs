@@ -0,0 +1,190 @@
+//! Read changed files and added lines through the `git` CLI.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Which changes to inspect.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffRange {
+    /// Index against HEAD (`git diff --cached`).
+    Staged,
+    /// Commit range `base..head`.
+    Commits { base: String, head: String },
+}
+
+impl DiffRange {
+    /// Parse `base..head`, `base...head` (merge base) or a single `base` (against HEAD).
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (base, head) = if let Some((base, head)) = spec.split_once("...") {
+            (format!("{}...", base), head)
+        } else if let Some((base, head)) = spec.split_once("..") {
+            (base.to_string(), head)
+        } else {
+            (spec.to_string(), "")
+        };
+        if base.is_empty() || base == "..." {
+            return Err(format!("invalid range: {}", spec));
+        }
+        let head = if head.is_empty() { "HEAD" } else { head };
+        Ok(DiffRange::Commits {
+            base,
+            head: head.to_string(),
+        })
+    }
+
+    fn diff_args(&self) -> Vec<String> {
+        match self {
+            DiffRange::Staged => vec!["--cached".to_string()],
+            DiffRange::Commits { base, head } => match base.strip_suffix("...") {
+                Some(base) => vec![format!("{}...{}", base, head)],
+                None => vec![base.clone(), head.clone()],
+            },
+        }
+    }
+
+    /// `git show` spec for the new version of `path`.
+    fn blob_spec(&self, path: &str) -> String {
+        match self {
+            DiffRange::Staged => format!(":{}", path),
+            DiffRange::Commits { head, .. } => format!("{}:{}", head, path),
+        }
+    }
+}
+
+pub struct Repo {
+    root: PathBuf,
+}
+
+impl Repo {
+    /// Locate the repository containing the current directory.
+    pub fn discover() -> Result<Self, String> {
+        let output = run_git(Path::new("."), &["rev-parse", "--show-toplevel"])?;
+        Ok(Self {
+            root: PathBuf::from(output.trim_end()),
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn git(&self, args: &[&str]) -> Result<String, String> {
+        run_git(&self.root, args)
+    }
+
+    /// Added or modified files (repo-relative), excluding deletions.
+    pub fn changed_files(&self, range: &DiffRange) -> Result<Vec<String>, String> {
+        let extra = range.diff_args();
+        let mut args = vec!["diff", "--name-only", "--diff-filter=AMR", "-z"];
+        args.extend(extra.iter().map(String::as_str));
+        let output = self.git(&args)?;
+        Ok(output
+            .split('\0')
+            .filter(|p| !p.is_empty())
+            .map(String::from)
+            .collect())
+    }
+
+    /// Content of `path` on the new side of the range.
+    pub fn read_blob(&self, range: &DiffRange, path: &str) -> Result<String, String> {
+        self.git(&["show", &range.blob_spec(path)])
+    }
+
+    /// New-side line numbers added or modified in `path`.
+    pub fn added_lines(&self, range: &DiffRange, path: &str) -> Result<HashSet<u32>, String> {
+        let extra = range.diff_args();
+        let mut args = vec!["diff", "-U0", "--no-color", "--no-ext-diff"];
+        args.extend(extra.iter().map(String::as_str));
+        args.extend(["--", path]);
+        Ok(parse_added_lines(&self.git(&args)?))
+    }
+}
+
+fn run_git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|e| format!("failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Collect new-side lines from `@@ -a,b +c,d @@` hunk headers of a `-U0` diff.
+fn parse_added_lines(diff: &str) -> HashSet<u32> {
+    let mut lines = HashSet::new();
+    for header in diff.lines().filter(|l| l.starts_with("@@ ")) {
+        let Some(new) = header.split_whitespace().find(|part| part.starts_with('+')) else {
+            continue;
+        };
+        let mut parts = new[1..].splitn(2, ',');
+        let start: u32 = parts.next().and_then(|s| s.parse().ok()).unwrap_or(0);
+        let count: u32 = parts.next().and_then(|s| s.parse().ok()).unwrap_or(1);
+        lines.extend(start..start + count);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ranges() {
+        assert_eq!(
+            DiffRange::parse("main..feature").unwrap(),
+            DiffRange::Commits {
+                base: "main".to_string(),
+                head: "feature".to_string()
+            }
+        );
+        assert_eq!(
+            DiffRange::parse("origin/main").unwrap(),
+            DiffRange::Commits {
+                base: "origin/main".to_string(),
+                head: "HEAD".to_string()
+            }
+        );
+        let merge_base = DiffRange::parse("main...HEAD").unwrap();
+        assert_eq!(merge_base.diff_args(), ["main...HEAD"]);
+        assert!(DiffRange::parse("..HEAD").is_err());
+    }
+
+    #[test]
+    fn blob_specs() {
+        assert_eq!(DiffRange::Staged.blob_spec("src/a.ts"), ":src/a.ts");
+        let range = DiffRange::parse("a..b").unwrap();
+        assert_eq!(range.blob_spec("src/a.ts"), "b:src/a.ts");
+    }
+
+    #[test]
+    fn parses_added_lines_from_hunks() {
+        let diff = "\
+diff --git a/src/a.ts b/src/a.ts
+--- a/src/a.ts
++++ b/src/a.ts
+@@ -3 +3 @@ fn
+-old
++new
+@@ -10,0 +11,3 @@
++a
++b
++c
+@@ -20,2 +23,0 @@
+-gone
+-gone
+";
+        let lines = parse_added_lines(diff);
+        let mut sorted: Vec<_> = lines.into_iter().collect();
+        sorted.sort();
+        assert_eq!(sorted, [3, 11, 12, 13]);
+    }
+}
@@ -0,0 +1,342 @@
+//! Import graph of a repository for cycle detection.
+//!
+//! Edges are runtime imports between project files (type-only and dynamic
+//! imports are left out). The graph is cached on disk per repository and
+//! built lazily: a path search refreshes only the files it visits, re-reading
+//! those whose mtime changed and re-parsing those whose content hash changed
+//! too. There is no repository walk, so a check stays within the hook timeout
+//! even with a cold cache.
+
+use crate::baseline::relative_path;
+use crate::fingerprint;
+use crate::imports::{self, ImportKind, Resolver, Target};
+use crate::rules::RE_JS_FILE;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+const VERSION: u32 = 1;
+
+const MAX_FILE_SIZE: u64 = 10_000_000; // 10MB limit
+
+/// Skip re-checking a file that was checked this recently, so `guardrails
+/// check` doesn't stat the same files once per checked file.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct Node {
+    /// Nanoseconds since the epoch.
+    mtime: u64,
+    hash: String,
+    /// Repo-relative paths of imported project files.
+    imports: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Cache {
+    version: u32,
+    files: BTreeMap<String, Node>,
+}
+
+/// Cache file for `root` under `$XDG_CACHE_HOME` or `~/.cache`.
+pub fn cache_path(root: &Path) -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".cache")))?;
+    let key = fingerprint::hash(&root.to_string_lossy());
+    Some(
+        base.join("guardrails")
+            .join("import-graph")
+            .join(format!("{}.json", key)),
+    )
+}
+
+fn mtime(metadata: &Metadata) -> Option<u64> {
+    let modified = metadata.modified().ok()?;
+    let since = modified.duration_since(UNIX_EPOCH).unwrap_or_default();
+    Some(since.as_nanos() as u64)
+}
+
+/// Runtime imports of `file` (absolute) that point at files inside `root`.
+pub fn project_imports(
+    root: &Path,
+    resolver: &Resolver,
+    file: &Path,
+    content: &str,
+) -> Vec<(u32, String)> {
+    let mut found: Vec<(u32, String)> = Vec::new();
+    for import in imports::parse(content, &file.to_string_lossy()) {
+        if import.type_only || import.kind == ImportKind::Dynamic {
+            continue;
+        }
+        let Target::File(target) = resolver.resolve(file, &import.specifier) else {
+            continue;
+        };
+        if !target.is_file() {
+            continue;
+        }
+        let Some(relative) = relative_path(root, &target) else {
+            continue;
+        };
+        if !found.iter().any(|(_, r)| *r == relative) {
+            found.push((import.line, relative));
+        }
+    }
+    found
+}
+
+pub struct Graph {
+    root: PathBuf,
+    cache_path: Option<PathBuf>,
+    files: BTreeMap<String, Node>,
+    /// When each file was last compared with the disk.
+    checked: HashMap<String, Instant>,
+    changed: bool,
+}
+
+impl Graph {
+    /// The cached graph for `root`, or an empty one.
+    pub fn load(root: &Path, cache_path: Option<PathBuf>) -> Graph {
+        let files = cache_path
+            .as_deref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|text| serde_json::from_str::<Cache>(&text).ok())
+            .filter(|c| c.version == VERSION)
+            .map(|c| c.files)
+            .unwrap_or_default();
+        Graph {
+            root: root.to_path_buf(),
+            cache_path,
+            files,
+            checked: HashMap::new(),
+            changed: false,
+        }
+    }
+
+    /// Imports of `relative`, re-read from disk if it changed since it was
+    /// cached. None if the file is gone.
+    fn imports(&mut self, resolver: &Resolver, relative: &str) -> Option<Vec<String>> {
+        if !RE_JS_FILE.is_match(relative) {
+            return Some(Vec::new());
+        }
+        if self
+            .checked
+            .get(relative)
+            .is_some_and(|t| t.elapsed() < REFRESH_INTERVAL)
+        {
+            return self.files.get(relative).map(|n| n.imports.clone());
+        }
+        self.checked.insert(relative.to_string(), Instant::now());
+
+        let path = self.root.join(relative);
+        let metadata = std::fs::metadata(&path)
+            .ok()
+            .filter(|m| m.is_file() && m.len() <= MAX_FILE_SIZE);
+        let Some(mtime) = metadata.as_ref().and_then(mtime) else {
+            self.changed |= self.files.remove(relative).is_some();
+            return None;
+        };
+
+        let cached = self.files.get(relative);
+        if let Some(node) = cached.filter(|n| n.mtime == mtime) {
+            return Some(node.imports.clone());
+        }
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            self.changed |= self.files.remove(relative).is_some();
+            return None;
+        };
+        let hash = fingerprint::hash(&content);
+        let imports = match cached {
+            Some(node) if node.hash == hash => node.imports.clone(),
+            _ => project_imports(&self.root, resolver, &path, &content)
+                .into_iter()
+                .map(|(_, target)| target)
+                .collect(),
+        };
+        self.files.insert(
+            relative.to_string(),
+            Node {
+                mtime,
+                hash,
+                imports: imports.clone(),
+            },
+        );
+        self.changed = true;
+        Some(imports)
+    }
+
+    /// Save the graph if a search changed it.
+    pub fn save_if_changed(&mut self) {
+        if std::mem::take(&mut self.changed) {
+            self.save();
+        }
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.cache_path else {
+            return;
+        };
+        let cache = Cache {
+            version: VERSION,
+            files: self.files.clone(),
+        };
+        let Ok(json) = serde_json::to_string(&cache) else {
+            return;
+        };
+        // Write then rename, so concurrent hook processes never read a partial file.
+        let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+        let result = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(&tmp, json))
+            .and_then(|_| std::fs::rename(&tmp, path));
+        if let Err(e) = result {
+            eprintln!(
+                "guardrails: warning: cannot write import graph cache {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+
+    /// Shortest import path from `from` to `to`, both repo-relative, inclusive.
+    /// Every file visited on the way is refreshed first.
+    pub fn path(&mut self, resolver: &Resolver, from: &str, to: &str) -> Option<Vec<String>> {
+        let mut parent: HashMap<String, String> = HashMap::new();
+        let mut queue = VecDeque::from([from.to_string()]);
+        parent.insert(from.to_string(), from.to_string());
+
+        while let Some(node) = queue.pop_front() {
+            if node == to {
+                let mut path = vec![node];
+                while path[path.len() - 1] != from {
+                    path.push(parent[&path[path.len() - 1]].clone());
+                }
+                path.reverse();
+                return Some(path);
+            }
+            let Some(imports) = self.imports(resolver, &node) else {
+                continue;
+            };
+            for next in imports {
+                if !parent.contains_key(&next) {
+                    parent.insert(next.clone(), node.clone());
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    #[cfg(test)]
+    fn imports_of(&self, file: &str) -> Option<&[String]> {
+        self.files.get(file).map(|n| n.imports.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, content: &str) {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    /// Write and move the mtime forward, so the change is seen even on
+    /// filesystems with coarse timestamps.
+    fn touch(path: &Path, content: &str) {
+        std::fs::write(path, content).unwrap();
+        let later = std::time::SystemTime::now() + Duration::from_secs(5);
+        std::fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+    }
+
+    fn repo() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir(root.join(".git")).unwrap();
+        write(
+            root,
+            "src/services/api.ts",
+            "import { useUser } from '../hooks/useUser';\n",
+        );
+        write(
+            root,
+            "src/hooks/useUser.ts",
+            "import type { User } from '../types';\nimport { cache } from './cache';\n",
+        );
+        write(
+            root,
+            "src/hooks/cache.ts",
+            "export const cache = new Map();\n",
+        );
+        write(root, "src/types.ts", "export type User = {};\n");
+        dir
+    }
+
+    #[test]
+    fn builds_runtime_edges_lazily() {
+        let dir = repo();
+        let resolver = Resolver::default();
+        let mut graph = Graph::load(dir.path(), None);
+        assert_eq!(
+            graph.path(&resolver, "src/services/api.ts", "src/hooks/cache.ts"),
+            Some(vec![
+                "src/services/api.ts".to_string(),
+                "src/hooks/useUser.ts".to_string(),
+                "src/hooks/cache.ts".to_string(),
+            ])
+        );
+        assert_eq!(
+            graph.imports_of("src/hooks/useUser.ts"),
+            Some(&["src/hooks/cache.ts".to_string()][..])
+        );
+        // Only imported at type level, so never visited.
+        assert!(graph.imports_of("src/types.ts").is_none());
+        assert_eq!(
+            graph.path(&resolver, "src/hooks/cache.ts", "src/services/api.ts"),
+            None
+        );
+    }
+
+    #[test]
+    fn cache_is_reused_and_updated() {
+        let dir = repo();
+        let root = dir.path();
+        let cache = root.join("cache/graph.json");
+        let resolver = Resolver::default();
+
+        let mut graph = Graph::load(root, Some(cache.clone()));
+        graph.path(&resolver, "src/services/api.ts", "src/types.ts");
+        graph.save_if_changed();
+        assert!(cache.is_file());
+
+        touch(
+            &root.join("src/hooks/cache.ts"),
+            "import { api } from '../services/api';\n",
+        );
+        std::fs::remove_file(root.join("src/hooks/useUser.ts")).unwrap();
+        let mut graph = Graph::load(root, Some(cache.clone()));
+        assert_eq!(graph.imports_of("src/hooks/cache.ts"), Some(&[][..]));
+        assert_eq!(
+            graph.path(&resolver, "src/hooks/cache.ts", "src/services/api.ts"),
+            Some(vec![
+                "src/hooks/cache.ts".to_string(),
+                "src/services/api.ts".to_string(),
+            ])
+        );
+        assert_eq!(
+            graph.path(&resolver, "src/services/api.ts", "src/hooks/cache.ts"),
+            None
+        );
+        assert!(graph.imports_of("src/hooks/useUser.ts").is_none());
+    }
+}
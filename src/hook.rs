@@ -0,0 +1,338 @@
+//! Claude Code PreToolUse hook: checks one tool payload and renders the result.
+//!
+//! The outcome is returned instead of printed so the same code serves both the
+//! in-process hook and the `guardrails serve` daemon.
+
+use crate::config::{guardrails_file_write, Config, OutputMode};
+use crate::input::{get_file_change, ToolInput};
+use crate::notebook;
+use crate::reporter::{
+    format_asks, format_hook_output, format_omitted, format_suppressions, format_violations,
+    format_warnings, Report,
+};
+use crate::rules::{self, ReadPolicy, Violation};
+use crate::runner::{Checked, Runner};
+use once_cell::unsync::OnceCell;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::path::PathBuf;
+
+/// What the hook process should print and exit with.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Outcome {
+    pub stdout: String,
+    pub stderr: String,
+    pub code: i32,
+}
+
+impl Outcome {
+    fn error(message: String) -> Self {
+        Self {
+            stderr: message,
+            code: 1,
+            ..Self::default()
+        }
+    }
+}
+
+/// Config plus a lazily built runner, so Bash and Read checks never start biome.
+pub struct Hook {
+    config: Config,
+    biome_server: bool,
+    runner: OnceCell<Runner>,
+}
+
+impl Hook {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            biome_server: false,
+            runner: OnceCell::new(),
+        }
+    }
+
+    /// Lint through a running `biome start` server.
+    pub fn with_biome_server(mut self) -> Self {
+        self.biome_server = true;
+        self
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    fn runner(&self) -> &Runner {
+        self.runner.get_or_init(|| {
+            let runner = Runner::new(&self.config);
+            if self.biome_server {
+                runner.with_biome_server()
+            } else {
+                runner
+            }
+        })
+    }
+
+    /// Findings for the written file, with its new content for code frames.
+    fn check_file_change(&self, input: &ToolInput) -> Option<(Checked, String)> {
+        let change = get_file_change(input)?;
+
+        let before = change
+            .before
+            .as_deref()
+            .filter(|_| self.config.only_new_violations);
+
+        let mut checked = self
+            .runner()
+            .check_change(&change.file_path, before, &change.after);
+        checked
+            .violations
+            .extend(guardrails_file_write(&change.file_path));
+        Some((checked, change.after))
+    }
+
+    fn check_notebook(&self, input: &ToolInput) -> Option<(Checked, String)> {
+        let cell = notebook::get_cell_change(input)?;
+
+        let before = cell
+            .before
+            .as_deref()
+            .filter(|_| self.config.only_new_violations);
+        let mut checked = self
+            .runner()
+            .check_change(&cell.check_path, before, &cell.after);
+
+        let suppressed = checked.suppressed.iter_mut().map(|s| &mut s.violation);
+        for v in checked.violations.iter_mut().chain(suppressed) {
+            v.file = cell.location.clone();
+        }
+
+        Some((checked, cell.after))
+    }
+
+    fn check_bash(&self, input: &ToolInput) -> Option<Vec<Violation>> {
+        let command = input
+            .tool_input
+            .command
+            .as_deref()
+            .filter(|c| !c.trim().is_empty())?;
+
+        if !self.config.rules.bash.enabled {
+            return Some(Vec::new());
+        }
+
+        let mut violations =
+            rules::check_command(command, &self.config.bash, &rules::git_current_branch);
+        self.config.rules.bash.apply(&mut violations);
+        Some(violations)
+    }
+
+    fn check_read(&self, input: &ToolInput) -> Option<Vec<Violation>> {
+        let data = &input.tool_input;
+        let paths: Vec<&str> = match input.tool_name.as_str() {
+            "Read" => vec![data.file_path.as_deref()?],
+            "Grep" => [data.path.as_deref(), data.glob.as_deref()]
+                .into_iter()
+                .flatten()
+                .collect(),
+            _ => [data.pattern.as_deref(), data.path.as_deref()]
+                .into_iter()
+                .flatten()
+                .collect(),
+        };
+
+        if !self.config.rules.sensitive_read.enabled {
+            return Some(Vec::new());
+        }
+
+        let mut violations = ReadPolicy::new(&self.config.read).check(&input.tool_name, &paths);
+        self.config.rules.sensitive_read.apply(&mut violations);
+        Some(violations)
+    }
+
+    /// Check a parsed hook payload.
+    pub fn check(&self, input: &ToolInput) -> Outcome {
+        if !self.config.enabled {
+            return Outcome::default();
+        }
+
+        let checked = match input.tool_name.as_str() {
+            "Bash" => self.check_bash(input).map(|v| (Checked::from(v), None)),
+            "Read" | "Grep" | "Glob" => self.check_read(input).map(|v| (Checked::from(v), None)),
+            "NotebookEdit" => self.check_notebook(input).map(|(c, s)| (c, Some(s))),
+            _ => self.check_file_change(input).map(|(c, s)| (c, Some(s))),
+        };
+
+        let Some((checked, source)) = checked else {
+            return Outcome {
+                stderr: format!(
+                    "guardrails: skipping {} (unsupported or empty)",
+                    input.tool_name
+                ),
+                ..Outcome::default()
+            };
+        };
+
+        let mut report =
+            Report::new(&checked.violations, &self.config).with_suppressed(&checked.suppressed);
+        if let Some(source) = &source {
+            report = report.with_source(source);
+        }
+        let mut outcome = Outcome::default();
+
+        if self.config.output == OutputMode::Json {
+            if let Some(output) = format_hook_output(&report) {
+                outcome.stdout = output;
+            }
+            return outcome;
+        }
+
+        let mut sections = Vec::new();
+
+        if !report.suppressed.is_empty() {
+            sections.push(format_suppressions(&report.suppressed));
+        }
+
+        if !report.allow.is_empty() {
+            sections.push(format_warnings(&report.allow));
+        }
+
+        // Exit codes cannot prompt the user, so "ask" blocks in text mode.
+        if !report.ask.is_empty() {
+            let mut asks = format_asks(&report);
+            let _ = write!(
+                asks,
+                "\nguardrails: set \"output\": \"json\" to prompt for approval instead of blocking"
+            );
+            sections.push(asks);
+        }
+
+        if !report.deny.is_empty() {
+            sections.push(format_violations(&report));
+        }
+
+        if report.omitted > 0 {
+            sections.push(format_omitted(report.omitted));
+        }
+
+        outcome.stderr = sections.join("\n");
+        if !report.deny.is_empty() || !report.ask.is_empty() {
+            outcome.code = 2;
+        }
+        outcome
+    }
+}
+
+pub fn parse_input(input_str: &str) -> Result<ToolInput, Outcome> {
+    serde_json::from_str(input_str)
+        .map_err(|e| Outcome::error(format!("guardrails: invalid JSON input: {}", e)))
+}
+
+/// The path whose project config applies: the touched file, or the cwd for
+/// commands and searches without a path.
+fn config_target(input: &ToolInput) -> PathBuf {
+    let data = &input.tool_input;
+    let path = match input.tool_name.as_str() {
+        "Bash" => None,
+        "Grep" | "Glob" => data.path.as_deref(),
+        "NotebookEdit" => data.notebook_path.as_deref(),
+        _ => data.file_path.as_deref(),
+    };
+    path.filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_default()
+}
+
+/// Effective config for a payload, including project `.guardrails.json` files.
+pub fn config_for(input: &ToolInput) -> Config {
+    Config::load_for(&config_target(input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(config: Config, input: &str) -> Outcome {
+        match parse_input(input) {
+            Ok(input) => Hook::new(config).check(&input),
+            Err(outcome) => outcome,
+        }
+    }
+
+    const RM_RF: &str = r#"{"tool_name":"Bash","tool_input":{"command":"rm -rf /"}}"#;
+
+    #[test]
+    fn blocks_with_exit_code_in_text_mode() {
+        let outcome = run(Config::default(), RM_RF);
+        assert_eq!(outcome.code, 2);
+        assert!(outcome.stderr.contains("GUARDRAILS"));
+        assert!(outcome.stdout.is_empty());
+    }
+
+    #[test]
+    fn json_mode_writes_decision_to_stdout() {
+        let config = Config {
+            output: OutputMode::Json,
+            ..Config::default()
+        };
+        let outcome = run(config, RM_RF);
+        assert_eq!(outcome.code, 0);
+        assert!(outcome.stdout.contains("\"permissionDecision\":\"deny\""));
+    }
+
+    #[test]
+    fn blocking_report_shows_code_frame() {
+        let input = r#"{"tool_name":"Write","tool_input":{"file_path":"/nonexistent/src/a.ts","content":"const a = 1;\nel.innerHTML = x;\n"}}"#;
+        let config: Config = serde_json::from_str(r#"{"rules": {"biome": false}}"#).unwrap();
+        let outcome = run(config, input);
+        assert_eq!(outcome.code, 2);
+        assert!(outcome
+            .stderr
+            .contains("    > 2 | el.innerHTML = x;\n        |   ^^^^^^^^^^^^"));
+
+        let config: Config =
+            serde_json::from_str(r#"{"rules": {"biome": false}, "codeFrame": {"enabled": false}}"#)
+                .unwrap();
+        assert!(!run(config, input).stderr.contains(" | "));
+    }
+
+    #[test]
+    fn config_writes_ask_for_approval() {
+        let config = Config {
+            output: OutputMode::Json,
+            ..Config::default()
+        };
+        let input = r#"{"tool_name":"Write","tool_input":{"file_path":"/tmp/repo/.guardrails.json","content":"{\"enabled\": false}"}}"#;
+        let outcome = run(config, input);
+        assert!(outcome.stdout.contains("\"permissionDecision\":\"ask\""));
+        assert!(outcome.stdout.contains("guardrails-config"));
+    }
+
+    #[test]
+    fn invalid_json_is_an_error() {
+        assert_eq!(run(Config::default(), "{").code, 1);
+    }
+
+    #[test]
+    fn disabled_config_allows_everything() {
+        let config = Config {
+            enabled: false,
+            ..Config::default()
+        };
+        assert_eq!(run(config, RM_RF), Outcome::default());
+    }
+
+    #[test]
+    fn config_target_follows_tool() {
+        let target = |json: &str| config_target(&parse_input(json).ok().unwrap());
+        assert_eq!(
+            target(r#"{"tool_name":"Write","tool_input":{"file_path":"/r/a.ts","content":"x"}}"#),
+            PathBuf::from("/r/a.ts")
+        );
+        assert_eq!(
+            target(r#"{"tool_name":"NotebookEdit","tool_input":{"notebook_path":"/r/n.ipynb"}}"#),
+            PathBuf::from("/r/n.ipynb")
+        );
+        assert_eq!(target(RM_RF), std::env::current_dir().unwrap());
+    }
+}
@@ -0,0 +1,655 @@
+//! Import specifiers and where they point.
+//!
+//! `parse` finds `import`/`export ... from`, side-effect imports, `require()`
+//! and dynamic `import()`. `Resolver` maps a specifier to a file using relative
+//! paths, tsconfig `baseUrl`/`paths` and package.json `imports`; anything else
+//! is a package.
+
+use crate::lexer::{is_jsx_file, mask_comments};
+use crate::rules::Span;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+static RE_FROM: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"(?m)(?:^|;)[ \t]*(import|export)\s+(type\s+)?([\w$*{}\s,]+?)\s*from\s*['"]([^'"\n]+)['"]"#,
+    )
+    .expect("RE_FROM: invalid regex")
+});
+static RE_SIDE_EFFECT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?m)(?:^|;)[ \t]*import\s*['"]([^'"\n]+)['"]"#)
+        .expect("RE_SIDE_EFFECT: invalid regex")
+});
+static RE_REQUIRE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\brequire\s*\(\s*['"]([^'"\n]+)['"]\s*\)"#).expect("RE_REQUIRE: invalid regex")
+});
+static RE_DYNAMIC: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\bimport\s*\(\s*['"]([^'"\n]+)['"]"#).expect("RE_DYNAMIC: invalid regex")
+});
+static RE_NAMESPACE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\*\s*as\s+[\w$]+$").expect("RE_NAMESPACE: invalid regex"));
+static RE_DEFAULT_BINDING: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^([\w$]+)\s*(?:,|$)").expect("RE_DEFAULT_BINDING: invalid regex"));
+
+/// Extensions tried, in order, for extensionless specifiers.
+const EXTENSIONS: &[&str] = &["ts", "tsx", "d.ts", "js", "jsx", "mjs", "cjs"];
+
+/// Max depth of tsconfig `extends` chains.
+const MAX_EXTENDS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportKind {
+    Import,
+    Export,
+    /// `import 'x'`
+    SideEffect,
+    Require,
+    Dynamic,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Import {
+    pub line: u32,
+    pub kind: ImportKind,
+    pub specifier: String,
+    /// Where the specifier sits, without its quotes.
+    pub span: Span,
+    /// Bindings between `import`/`export` and `from`, e.g. `_, { map }`.
+    pub clause: Option<String>,
+    /// `import type` / `export type`, erased at runtime.
+    pub type_only: bool,
+}
+
+impl Import {
+    /// `import * as X from`
+    pub fn is_namespace(&self) -> bool {
+        self.clause
+            .as_deref()
+            .is_some_and(|c| RE_NAMESPACE.is_match(c))
+    }
+
+    /// `import X from` or `import X, { y } from`
+    pub fn default_binding(&self) -> Option<&str> {
+        if self.kind != ImportKind::Import {
+            return None;
+        }
+        let clause = self.clause.as_deref()?;
+        RE_DEFAULT_BINDING
+            .captures(clause)
+            .and_then(|c| c.get(1))
+            .map(|m| m.as_str())
+    }
+}
+
+/// Imports in source order. Imports inside comments are skipped.
+pub fn parse(content: &str, file_path: &str) -> Vec<Import> {
+    // Comments are blanked, so offsets still map to the original line numbers.
+    let code = mask_comments(content, is_jsx_file(file_path));
+    let line_at = |offset: usize| code[..offset].matches('\n').count() as u32 + 1;
+
+    let mut imports = Vec::new();
+    for cap in RE_FROM.captures_iter(&code) {
+        let keyword = cap.get(1).expect("group 1 always matches");
+        let specifier = cap.get(4).expect("group 4 always matches");
+        imports.push((
+            keyword.start(),
+            Import {
+                line: line_at(keyword.start()),
+                kind: if keyword.as_str() == "export" {
+                    ImportKind::Export
+                } else {
+                    ImportKind::Import
+                },
+                specifier: specifier.as_str().to_string(),
+                span: Span::in_text(1, content, specifier.start(), specifier.end()),
+                clause: Some(cap[3].split_whitespace().collect::<Vec<_>>().join(" ")),
+                type_only: cap.get(2).is_some(),
+            },
+        ));
+    }
+    for (re, kind) in [
+        (&*RE_SIDE_EFFECT, ImportKind::SideEffect),
+        (&*RE_REQUIRE, ImportKind::Require),
+        (&*RE_DYNAMIC, ImportKind::Dynamic),
+    ] {
+        for cap in re.captures_iter(&code) {
+            let specifier = cap.get(1).expect("group 1 always matches");
+            imports.push((
+                specifier.start(),
+                Import {
+                    line: line_at(specifier.start()),
+                    kind,
+                    specifier: specifier.as_str().to_string(),
+                    span: Span::in_text(1, content, specifier.start(), specifier.end()),
+                    clause: None,
+                    type_only: false,
+                },
+            ));
+        }
+    }
+    imports.sort_by_key(|(offset, _)| *offset);
+    imports.into_iter().map(|(_, import)| import).collect()
+}
+
+/// What a specifier resolves to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    /// A project file. The path exists unless no candidate did, in which case it
+    /// is the lexical target without an extension.
+    File(PathBuf),
+    /// A package or builtin, by name (`lodash`, `@mui/icons-material`, `node:fs`).
+    Package(String),
+}
+
+/// Lexically resolve `.` and `..` components.
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// `path` made absolute against the current directory.
+pub fn absolute(path: &str) -> PathBuf {
+    std::env::current_dir()
+        .map(|cwd| normalize(&cwd.join(path)))
+        .unwrap_or_else(|_| PathBuf::from(path))
+}
+
+/// Strip `//` and `/* */` comments and trailing commas, which tsconfig allows.
+fn strip_jsonc(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => while chars.next_if(|&c| c != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            (',', _) => {
+                let rest = chars.clone().find(|c| !c.is_whitespace());
+                if !matches!(rest, Some('}' | ']')) {
+                    out.push(c);
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    let text = std::fs::read_to_string(path).ok()?;
+    match serde_json::from_str(&strip_jsonc(&text)) {
+        Ok(v) => Some(v),
+        Err(e) => {
+            eprintln!(
+                "guardrails: warning: cannot parse {}: {}",
+                path.display(),
+                e
+            );
+            None
+        }
+    }
+}
+
+/// The `*` match of `spec` against a tsconfig `paths` or package.json
+/// `imports` key, or "" for an exact key.
+fn match_pattern<'a>(pattern: &str, spec: &'a str) -> Option<&'a str> {
+    match pattern.split_once('*') {
+        Some((prefix, suffix)) => (spec.len() >= prefix.len() + suffix.len()
+            && spec.starts_with(prefix)
+            && spec.ends_with(suffix))
+        .then(|| &spec[prefix.len()..spec.len() - suffix.len()]),
+        None => (pattern == spec).then_some(""),
+    }
+}
+
+/// The key with the longest prefix before `*` that matches `spec`.
+fn best_match<'a, T>(entries: &'a [(String, T)], spec: &'a str) -> Option<(&'a str, &'a T)> {
+    entries
+        .iter()
+        .filter_map(|(pattern, value)| Some((pattern, match_pattern(pattern, spec)?, value)))
+        .max_by_key(|(pattern, _, _)| pattern.find('*').unwrap_or(pattern.len()))
+        .map(|(_, matched, value)| (matched, value))
+}
+
+/// An existing file for `path`, trying extensions, `.js` → `.ts` and `index` files.
+fn probe(path: &Path) -> Option<PathBuf> {
+    if path.is_file() {
+        return Some(path.to_path_buf());
+    }
+    let name = path.file_name()?.to_str()?;
+    let with_ext = |ext: &str| path.with_file_name(format!("{}.{}", name, ext));
+    if let Some(found) = EXTENSIONS.iter().map(|e| with_ext(e)).find(|p| p.is_file()) {
+        return Some(found);
+    }
+    // TypeScript ESM imports name the emitted `.js` file.
+    if let Some((stem, ext)) = name.rsplit_once('.') {
+        let ts_ext: &[&str] = match ext {
+            "js" => &["ts", "tsx"],
+            "jsx" => &["tsx"],
+            "mjs" => &["mts"],
+            "cjs" => &["cts"],
+            _ => &[],
+        };
+        if let Some(found) = ts_ext
+            .iter()
+            .map(|e| path.with_file_name(format!("{}.{}", stem, e)))
+            .find(|p| p.is_file())
+        {
+            return Some(found);
+        }
+    }
+    EXTENSIONS
+        .iter()
+        .map(|e| path.join(format!("index.{}", e)))
+        .find(|p| p.is_file())
+}
+
+/// The parts of a tsconfig that affect import resolution.
+#[derive(Debug, Default)]
+struct TsConfig {
+    base_url: Option<PathBuf>,
+    paths: Vec<(String, Vec<String>)>,
+    /// Directory `paths` are relative to when there is no `baseUrl`.
+    paths_dir: PathBuf,
+}
+
+impl TsConfig {
+    /// Read a tsconfig, following relative `extends`.
+    fn read(path: &Path, depth: usize) -> Option<TsConfig> {
+        let json = read_json(path)?;
+        let dir = path.parent()?;
+
+        let mut config = json
+            .get("extends")
+            .and_then(Value::as_str)
+            .filter(|e| e.starts_with('.') && depth < MAX_EXTENDS)
+            .and_then(|e| {
+                let parent = if e.ends_with(".json") {
+                    dir.join(e)
+                } else {
+                    dir.join(format!("{}.json", e))
+                };
+                TsConfig::read(&parent, depth + 1)
+            })
+            .unwrap_or_default();
+
+        let options = json.get("compilerOptions");
+        if let Some(base) = options
+            .and_then(|o| o.get("baseUrl"))
+            .and_then(Value::as_str)
+        {
+            config.base_url = Some(normalize(&dir.join(base)));
+        }
+        if let Some(paths) = options
+            .and_then(|o| o.get("paths"))
+            .and_then(Value::as_object)
+        {
+            config.paths = paths
+                .iter()
+                .map(|(pattern, targets)| {
+                    let targets = targets
+                        .as_array()
+                        .map(|t| {
+                            t.iter()
+                                .filter_map(Value::as_str)
+                                .map(String::from)
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    (pattern.clone(), targets)
+                })
+                .collect();
+            config.paths_dir = dir.to_path_buf();
+        }
+        Some(config)
+    }
+
+    fn resolve(&self, spec: &str) -> Option<PathBuf> {
+        let base = self.base_url.as_deref().unwrap_or(&self.paths_dir);
+        if let Some((matched, targets)) = best_match(&self.paths, spec) {
+            let candidates: Vec<PathBuf> = targets
+                .iter()
+                .map(|t| normalize(&base.join(t.replacen('*', matched, 1))))
+                .collect();
+            return candidates
+                .iter()
+                .find_map(|c| probe(c))
+                .or_else(|| candidates.into_iter().next());
+        }
+        // Bare specifiers under baseUrl only count if such a file exists;
+        // otherwise they are packages.
+        self.base_url
+            .as_ref()
+            .and_then(|base| probe(&normalize(&base.join(spec))))
+    }
+}
+
+/// package.json `imports` (subpath imports starting with `#`).
+#[derive(Debug, Default)]
+struct PackageImports {
+    dir: PathBuf,
+    imports: Vec<(String, Value)>,
+}
+
+/// First target of a conditional export, preferring source-like conditions.
+fn pick_target(value: &Value) -> Option<&str> {
+    match value {
+        Value::String(s) => Some(s),
+        Value::Array(items) => items.iter().find_map(pick_target),
+        Value::Object(map) => ["types", "import", "default", "require", "node"]
+            .iter()
+            .filter_map(|k| map.get(*k))
+            .chain(map.values())
+            .find_map(pick_target),
+        _ => None,
+    }
+}
+
+impl PackageImports {
+    fn read(path: &Path) -> Option<PackageImports> {
+        let json = read_json(path)?;
+        Some(PackageImports {
+            dir: path.parent()?.to_path_buf(),
+            imports: json
+                .get("imports")
+                .and_then(Value::as_object)
+                .map(|m| m.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+                .unwrap_or_default(),
+        })
+    }
+
+    fn resolve(&self, spec: &str) -> Option<PathBuf> {
+        let (matched, value) = best_match(&self.imports, spec)?;
+        let target = pick_target(value)?.replacen('*', matched, 1);
+        if !target.starts_with('.') {
+            return None;
+        }
+        let path = normalize(&self.dir.join(target));
+        Some(probe(&path).unwrap_or(path))
+    }
+}
+
+/// A parsed config file and the modification time it was read at.
+type Parsed<T> = (Option<SystemTime>, Option<Arc<T>>);
+
+/// Nearest config file per directory, re-read when it changes (the daemon is long-lived).
+struct ConfigCache<T> {
+    file_name: &'static str,
+    located: Mutex<HashMap<PathBuf, Option<PathBuf>>>,
+    parsed: Mutex<HashMap<PathBuf, Parsed<T>>>,
+}
+
+impl<T> ConfigCache<T> {
+    fn new(file_name: &'static str) -> Self {
+        Self {
+            file_name,
+            located: Mutex::new(HashMap::new()),
+            parsed: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn get(&self, dir: &Path, read: impl FnOnce(&Path) -> Option<T>) -> Option<Arc<T>> {
+        let path = {
+            let mut located = self.located.lock().unwrap_or_else(|e| e.into_inner());
+            located
+                .entry(dir.to_path_buf())
+                .or_insert_with(|| {
+                    for ancestor in dir.ancestors() {
+                        let path = ancestor.join(self.file_name);
+                        if path.is_file() {
+                            return Some(path);
+                        }
+                        if ancestor.join(".git").exists() {
+                            break;
+                        }
+                    }
+                    None
+                })
+                .clone()?
+        };
+
+        let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+        let mut parsed = self.parsed.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((cached_at, value)) = parsed.get(&path) {
+            if *cached_at == modified {
+                return value.clone();
+            }
+        }
+        let value = read(&path).map(Arc::new);
+        parsed.insert(path, (modified, value.clone()));
+        value
+    }
+}
+
+/// Resolves specifiers relative to the importing file.
+pub struct Resolver {
+    tsconfigs: ConfigCache<TsConfig>,
+    packages: ConfigCache<PackageImports>,
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        Self {
+            tsconfigs: ConfigCache::new("tsconfig.json"),
+            packages: ConfigCache::new("package.json"),
+        }
+    }
+}
+
+/// `lodash/fp` → `lodash`, `@mui/icons-material/Home` → `@mui/icons-material`.
+fn package_name(spec: &str) -> String {
+    let segments = if spec.starts_with('@') { 2 } else { 1 };
+    spec.splitn(segments + 1, '/')
+        .take(segments)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+impl Resolver {
+    /// Resolve `spec` imported from `from` (an absolute file path).
+    pub fn resolve(&self, from: &Path, spec: &str) -> Target {
+        let Some(dir) = from.parent() else {
+            return Target::Package(package_name(spec));
+        };
+        if spec == "." || spec == ".." || spec.starts_with("./") || spec.starts_with("../") {
+            let path = normalize(&dir.join(spec));
+            return Target::File(probe(&path).unwrap_or(path));
+        }
+        if spec.starts_with('/') {
+            return Target::File(PathBuf::from(spec));
+        }
+        if spec.starts_with('#') {
+            if let Some(path) = self
+                .packages
+                .get(dir, PackageImports::read)
+                .and_then(|p| p.resolve(spec))
+            {
+                return Target::File(path);
+            }
+        }
+        if let Some(path) = self
+            .tsconfigs
+            .get(dir, |p| TsConfig::read(p, 0))
+            .and_then(|t| t.resolve(spec))
+        {
+            return Target::File(path);
+        }
+        Target::Package(package_name(spec))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, content: &str) {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn specifiers(content: &str) -> Vec<(u32, ImportKind, String)> {
+        parse(content, "/src/a.ts")
+            .into_iter()
+            .map(|i| (i.line, i.kind, i.specifier))
+            .collect()
+    }
+
+    #[test]
+    fn parses_all_import_forms() {
+        let content = "import a from 'a';\nimport {\n  b,\n  c,\n} from \"b\";\nexport * from './c';\nimport './d.css'; import type { E } from 'e';\n// import x from 'commented';\nconst f = require('f');\nconst g = await import('./g');\n";
+        assert_eq!(
+            specifiers(content),
+            [
+                (1, ImportKind::Import, "a".to_string()),
+                (2, ImportKind::Import, "b".to_string()),
+                (6, ImportKind::Export, "./c".to_string()),
+                (7, ImportKind::SideEffect, "./d.css".to_string()),
+                (7, ImportKind::Import, "e".to_string()),
+                (9, ImportKind::Require, "f".to_string()),
+                (10, ImportKind::Dynamic, "./g".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn classifies_bindings() {
+        let imports = parse("import _ from 'lodash';\nimport * as Icons from 'icons';\nimport { map } from 'lodash-es';\nimport React, { useState } from 'react';\n", "/src/a.ts");
+        assert_eq!(imports[0].default_binding(), Some("_"));
+        assert!(imports[1].is_namespace());
+        assert_eq!(imports[1].default_binding(), None);
+        assert_eq!(imports[2].default_binding(), None);
+        assert_eq!(imports[3].default_binding(), Some("React"));
+        assert!(!imports[3].type_only);
+        assert!(parse("import type { A } from './a';\n", "/src/a.ts")[0].type_only);
+    }
+
+    #[test]
+    fn resolves_relative_imports_to_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, "src/domain/user.ts", "");
+        write(root, "src/domain/index.ts", "");
+        write(root, "src/lib/esm.ts", "");
+        let from = root.join("src/ui/Profile.tsx");
+
+        let resolver = Resolver::default();
+        let resolve = |spec| resolver.resolve(&from, spec);
+        assert_eq!(
+            resolve("../domain/user"),
+            Target::File(root.join("src/domain/user.ts"))
+        );
+        assert_eq!(
+            resolve("../domain"),
+            Target::File(root.join("src/domain/index.ts"))
+        );
+        assert_eq!(
+            resolve("../lib/esm.js"),
+            Target::File(root.join("src/lib/esm.ts"))
+        );
+        assert_eq!(
+            resolve("../missing/thing"),
+            Target::File(root.join("src/missing/thing"))
+        );
+        assert_eq!(
+            resolve("@mui/icons-material/Home"),
+            Target::Package("@mui/icons-material".to_string())
+        );
+        assert_eq!(resolve("lodash/fp"), Target::Package("lodash".to_string()));
+    }
+
+    #[test]
+    fn resolves_tsconfig_paths_and_base_url() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "tsconfig.base.json",
+            "{\n  // shared\n  \"compilerOptions\": {\"baseUrl\": \"src\", \"paths\": {\"@/*\": [\"*\"], \"@ui\": [\"ui/index.ts\"],},},\n}\n",
+        );
+        write(root, "tsconfig.json", r#"{"extends": "./tsconfig.base"}"#);
+        write(root, "src/domain/user.ts", "");
+        write(root, "src/shared/date.ts", "");
+        let from = root.join("src/ui/Profile.tsx");
+
+        let resolver = Resolver::default();
+        let resolve = |spec| resolver.resolve(&from, spec);
+        assert_eq!(
+            resolve("@/domain/user"),
+            Target::File(root.join("src/domain/user.ts"))
+        );
+        assert_eq!(resolve("@ui"), Target::File(root.join("src/ui/index.ts")));
+        assert_eq!(
+            resolve("shared/date"),
+            Target::File(root.join("src/shared/date.ts"))
+        );
+        assert_eq!(resolve("react"), Target::Package("react".to_string()));
+    }
+
+    #[test]
+    fn resolves_package_imports() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "package.json",
+            r##"{"imports": {"#db/*": {"types": "./src/db/*.ts", "default": "./dist/db/*.js"}, "#fs": "node:fs"}}"##,
+        );
+        write(root, "src/db/client.ts", "");
+        let from = root.join("src/app.ts");
+
+        let resolver = Resolver::default();
+        assert_eq!(
+            resolver.resolve(&from, "#db/client"),
+            Target::File(root.join("src/db/client.ts"))
+        );
+        assert_eq!(
+            resolver.resolve(&from, "#fs"),
+            Target::Package("#fs".to_string())
+        );
+    }
+
+    #[test]
+    fn strips_jsonc() {
+        let text = "{\"a\": \"// not a comment\", /* b */ \"c\": [1, 2,],\n}";
+        let value: Value = serde_json::from_str(&strip_jsonc(text)).unwrap();
+        assert_eq!(value["a"], "// not a comment");
+        assert_eq!(value["c"], serde_json::json!([1, 2]));
+    }
+}
@@ -0,0 +1,258 @@
+//! Hook payload parsing and reconstruction of the post-edit file.
+//!
+//! `Edit` and `MultiEdit` only carry the replaced fragments, so the edits are
+//! applied to the file on disk to give rules the whole file and real line numbers.
+
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct ToolInput {
+    pub tool_name: String,
+    pub tool_input: ToolInputData,
+}
+
+#[derive(Deserialize)]
+pub struct ToolInputData {
+    pub file_path: Option<String>,
+    pub content: Option<String>,
+    pub old_string: Option<String>,
+    pub new_string: Option<String>,
+    #[serde(default)]
+    pub replace_all: bool,
+    pub edits: Option<Vec<EditItem>>,
+    pub command: Option<String>,
+    pub path: Option<String>,
+    pub pattern: Option<String>,
+    pub glob: Option<String>,
+    pub notebook_path: Option<String>,
+    pub new_source: Option<String>,
+    pub cell_id: Option<String>,
+    pub cell_type: Option<String>,
+    pub edit_mode: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct EditItem {
+    pub old_string: Option<String>,
+    pub new_string: Option<String>,
+    #[serde(default)]
+    pub replace_all: bool,
+}
+
+/// Apply a single string replacement. Returns None if `old_string` is not found.
+/// An empty `old_string` on an empty file creates the file with `new_string`.
+fn apply_edit(content: &str, old: &str, new: &str, replace_all: bool) -> Option<String> {
+    if old.is_empty() {
+        return content.is_empty().then(|| new.to_string());
+    }
+    if !content.contains(old) {
+        return None;
+    }
+    if replace_all {
+        Some(content.replace(old, new))
+    } else {
+        Some(content.replacen(old, new, 1))
+    }
+}
+
+/// Apply edits in order, failing if any of them does not match.
+fn apply_edits<'a>(
+    original: &str,
+    edits: impl IntoIterator<Item = (&'a str, &'a str, bool)>,
+) -> Option<String> {
+    let mut content = original.to_string();
+    for (old, new, replace_all) in edits {
+        content = apply_edit(&content, old, new, replace_all)?;
+    }
+    Some(content)
+}
+
+fn read_file(path: &str) -> Option<String> {
+    match std::fs::read_to_string(path) {
+        Ok(s) => Some(s),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Some(String::new()),
+        Err(e) => {
+            eprintln!("guardrails: cannot read {}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// A file write as seen by the rules: the whole file before and after the operation.
+pub struct FileChange {
+    pub file_path: String,
+    /// None for new files, or when only edit fragments are available.
+    pub before: Option<String>,
+    pub after: String,
+}
+
+pub fn get_file_change(input: &ToolInput) -> Option<FileChange> {
+    get_file_change_with(input, read_file)
+}
+
+/// Resolve the content rules should see. `read` returns the current file
+/// (empty string if it does not exist yet), or None if it cannot be read.
+fn get_file_change_with(
+    input: &ToolInput,
+    read: impl Fn(&str) -> Option<String>,
+) -> Option<FileChange> {
+    let data = &input.tool_input;
+    let file_path = data.file_path.clone()?;
+
+    let (before, after) = match input.tool_name.as_str() {
+        "Write" => (read(&file_path), data.content.clone()?),
+        "Edit" => {
+            let new = data.new_string.as_deref()?;
+            let old = data.old_string.as_deref().unwrap_or("");
+            let original = read(&file_path);
+            match original
+                .as_deref()
+                .and_then(|o| apply_edits(o, [(old, new, data.replace_all)]))
+            {
+                Some(applied) => (original, applied),
+                None => {
+                    eprintln!(
+                        "guardrails: could not apply edit to {}, checking fragment only",
+                        file_path
+                    );
+                    (None, new.to_string())
+                }
+            }
+        }
+        "MultiEdit" => {
+            let edits = data.edits.as_ref()?;
+            let original = read(&file_path);
+            let applied = original.as_deref().and_then(|o| {
+                apply_edits(
+                    o,
+                    edits.iter().map(|e| {
+                        (
+                            e.old_string.as_deref().unwrap_or(""),
+                            e.new_string.as_deref().unwrap_or(""),
+                            e.replace_all,
+                        )
+                    }),
+                )
+            });
+            match applied {
+                Some(applied) => (original, applied),
+                None => {
+                    eprintln!(
+                        "guardrails: could not apply edits to {}, checking fragments only",
+                        file_path
+                    );
+                    let fragments = edits
+                        .iter()
+                        .filter_map(|e| e.new_string.clone())
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    (None, fragments)
+                }
+            }
+        }
+        _ => return None,
+    };
+
+    if file_path.is_empty() || after.is_empty() {
+        return None;
+    }
+
+    Some(FileChange {
+        file_path,
+        before: before.filter(|b| !b.is_empty()),
+        after,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> ToolInput {
+        serde_json::from_str(json).expect("valid tool input")
+    }
+
+    fn resolve(json: &str, disk: &str) -> Option<(String, String)> {
+        change(json, disk).map(|c| (c.file_path, c.after))
+    }
+
+    fn change(json: &str, disk: &str) -> Option<FileChange> {
+        let disk = disk.to_string();
+        get_file_change_with(&parse(json), move |_| Some(disk.clone()))
+    }
+
+    #[test]
+    fn write_uses_content() {
+        let json = r#"{"tool_name":"Write","tool_input":{"file_path":"/a.ts","content":"x"}}"#;
+        assert_eq!(resolve(json, "old").unwrap().1, "x");
+    }
+
+    #[test]
+    fn edit_applies_to_file_on_disk() {
+        let json = r#"{"tool_name":"Edit","tool_input":{"file_path":"/a.ts","old_string":"b","new_string":"B"}}"#;
+        assert_eq!(resolve(json, "a\nb\nc\nb").unwrap().1, "a\nB\nc\nb");
+    }
+
+    #[test]
+    fn edit_replace_all() {
+        let json = r#"{"tool_name":"Edit","tool_input":{"file_path":"/a.ts","old_string":"b","new_string":"B","replace_all":true}}"#;
+        assert_eq!(resolve(json, "a\nb\nc\nb").unwrap().1, "a\nB\nc\nB");
+    }
+
+    #[test]
+    fn edit_creates_new_file() {
+        let json = r#"{"tool_name":"Edit","tool_input":{"file_path":"/a.ts","old_string":"","new_string":"x"}}"#;
+        assert_eq!(resolve(json, "").unwrap().1, "x");
+    }
+
+    #[test]
+    fn edit_falls_back_to_fragment_when_unmatched() {
+        let json = r#"{"tool_name":"Edit","tool_input":{"file_path":"/a.ts","old_string":"zzz","new_string":"x"}}"#;
+        assert_eq!(resolve(json, "a\nb").unwrap().1, "x");
+    }
+
+    #[test]
+    fn multi_edit_applies_in_order() {
+        let json = r#"{"tool_name":"MultiEdit","tool_input":{"file_path":"/a.ts","edits":[
+            {"old_string":"a","new_string":"b"},
+            {"old_string":"b","new_string":"c","replace_all":true}
+        ]}}"#;
+        assert_eq!(resolve(json, "a b").unwrap().1, "c c");
+    }
+
+    #[test]
+    fn multi_edit_falls_back_to_fragments() {
+        let json = r#"{"tool_name":"MultiEdit","tool_input":{"file_path":"/a.ts","edits":[
+            {"old_string":"a","new_string":"x"},
+            {"old_string":"missing","new_string":"y"}
+        ]}}"#;
+        assert_eq!(resolve(json, "a").unwrap().1, "x\ny");
+    }
+
+    #[test]
+    fn keeps_original_as_before() {
+        let json = r#"{"tool_name":"Edit","tool_input":{"file_path":"/a.ts","old_string":"b","new_string":"B"}}"#;
+        assert_eq!(
+            change(json, "a\nb").unwrap().before.as_deref(),
+            Some("a\nb")
+        );
+    }
+
+    #[test]
+    fn new_file_has_no_before() {
+        let json = r#"{"tool_name":"Write","tool_input":{"file_path":"/a.ts","content":"x"}}"#;
+        assert!(change(json, "").unwrap().before.is_none());
+    }
+
+    #[test]
+    fn fragment_fallback_has_no_before() {
+        let json = r#"{"tool_name":"Edit","tool_input":{"file_path":"/a.ts","old_string":"zzz","new_string":"x"}}"#;
+        assert!(change(json, "a").unwrap().before.is_none());
+    }
+
+    #[test]
+    fn unsupported_tool_is_skipped() {
+        let json = r#"{"tool_name":"Read","tool_input":{"file_path":"/a.ts"}}"#;
+        assert!(resolve(json, "a").is_none());
+    }
+}
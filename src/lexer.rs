@@ -0,0 +1,708 @@
+//! JavaScript/TypeScript lexer shared by the rules that need to know what is
+//! code, what is a string and what is a comment.
+//!
+//! It produces a flat token stream rather than a syntax tree:
+//! - Strings, template literal chunks (interpolations are lexed as code),
+//!   regex literals and comments are single tokens.
+//! - `/` starts a regex literal unless the previous token ends an expression
+//!   (an identifier, literal, `)`, `]` or `}`), so `/https:\/\//` is a regex
+//!   and `a / b // c` a division followed by a comment.
+//! - In `.jsx` and `.tsx` files, `<` in expression position followed by a tag
+//!   name starts JSX. Text children become `JsxText` tokens, so
+//!   `<p>Don't // stop</p>` has neither a string nor a comment in it.
+//!   Attribute values are strings and `{...}` is code. A tag that is never
+//!   closed is lexed again as plain `<`. Other files never contain JSX, so
+//!   TypeScript casts like `<Foo>bar` and generics are always code.
+//! - JSX elements and template interpolations nest at most `MAX_NESTING`
+//!   deep, so hostile input can't overflow the stack.
+//!
+//! Token boundaries always fall on ASCII delimiters, so offsets are valid
+//! `str` indices even with multi-byte content.
+
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    LineComment,
+    BlockComment,
+    /// `'...'` or `"..."`, including JSX attribute values.
+    String,
+    /// A literal chunk of a template: from `` ` `` or `}` through `${` or `` ` ``.
+    Template,
+    Regex,
+    /// Identifiers and keywords.
+    Ident,
+    Number,
+    /// One punctuation character.
+    Punct,
+    JsxText,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Token {
+    pub fn text<'a>(&self, content: &'a str) -> &'a str {
+        &content[self.start..self.end]
+    }
+
+    pub fn is_comment(&self) -> bool {
+        matches!(self.kind, TokenKind::LineComment | TokenKind::BlockComment)
+    }
+
+    pub fn is_punct(&self, content: &str, c: u8) -> bool {
+        self.kind == TokenKind::Punct && content.as_bytes()[self.start] == c
+    }
+}
+
+/// Keywords after which `/` starts a regex rather than a division.
+const KEYWORDS_BEFORE_EXPRESSION: &[&str] = &[
+    "return",
+    "typeof",
+    "instanceof",
+    "in",
+    "of",
+    "new",
+    "delete",
+    "void",
+    "throw",
+    "case",
+    "do",
+    "else",
+    "yield",
+    "await",
+];
+
+/// Deepest nesting of JSX elements and template interpolations that is lexed.
+const MAX_NESTING: usize = 128;
+
+/// Whether `file_path` may contain JSX.
+pub fn is_jsx_file(file_path: &str) -> bool {
+    file_path.ends_with(".jsx") || file_path.ends_with(".tsx")
+}
+
+fn is_ident_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || b == b'$' || b >= 0x80
+}
+
+struct Lexer<'a> {
+    src: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+    tokens: Vec<Token>,
+    jsx: bool,
+    /// Current nesting of JSX elements and template interpolations.
+    depth: usize,
+    /// Offsets where a JSX element failed to lex, so it isn't tried again.
+    failed_jsx: HashSet<usize>,
+}
+
+impl<'a> Lexer<'a> {
+    fn peek(&self, ahead: usize) -> Option<u8> {
+        self.bytes.get(self.pos + ahead).copied()
+    }
+
+    fn push(&mut self, kind: TokenKind, start: usize) {
+        self.tokens.push(Token {
+            kind,
+            start,
+            end: self.pos,
+        });
+    }
+
+    /// Whether the previous token leaves the lexer where an expression may start.
+    fn expression_allowed(&self) -> bool {
+        let Some(prev) = self.tokens.iter().rev().find(|t| !t.is_comment()) else {
+            return true;
+        };
+        let text = prev.text(self.src);
+        match prev.kind {
+            TokenKind::Ident => KEYWORDS_BEFORE_EXPRESSION.contains(&text),
+            TokenKind::Punct => !matches!(text, ")" | "]" | "}"),
+            TokenKind::Template => text.ends_with("${"),
+            TokenKind::LineComment | TokenKind::BlockComment => true,
+            TokenKind::String | TokenKind::Regex | TokenKind::Number | TokenKind::JsxText => false,
+        }
+    }
+
+    /// Lex code until the end of input or, with `in_braces`, an unmatched `}`,
+    /// which is left unconsumed.
+    fn code(&mut self, in_braces: bool) {
+        let mut depth = 0usize;
+        while let Some(b) = self.peek(0) {
+            let start = self.pos;
+            match b {
+                b' ' | b'\t' | b'\n' | b'\r' | 0x0b | 0x0c => self.pos += 1,
+                b'/' if self.peek(1) == Some(b'/') => self.line_comment(),
+                b'#' if start == 0 && self.peek(1) == Some(b'!') => self.line_comment(),
+                b'/' if self.peek(1) == Some(b'*') => {
+                    self.pos = match self.src[start + 2..].find("*/") {
+                        Some(i) => start + 2 + i + 2,
+                        None => self.bytes.len(),
+                    };
+                    self.push(TokenKind::BlockComment, start);
+                }
+                b'/' if self.expression_allowed() && self.regex() => {
+                    self.push(TokenKind::Regex, start)
+                }
+                b'\'' | b'"' => {
+                    self.string(b);
+                    self.push(TokenKind::String, start);
+                }
+                b'`' => self.template(),
+                b'<' if self.expression_allowed() && self.jsx_element() => {}
+                b'{' => {
+                    depth += 1;
+                    self.pos += 1;
+                    self.push(TokenKind::Punct, start);
+                }
+                b'}' if in_braces && depth == 0 => return,
+                b'}' => {
+                    depth = depth.saturating_sub(1);
+                    self.pos += 1;
+                    self.push(TokenKind::Punct, start);
+                }
+                b'0'..=b'9' => {
+                    self.pos += 1;
+                    while self.peek(0).is_some_and(|b| is_ident_byte(b) || b == b'.') {
+                        self.pos += 1;
+                    }
+                    self.push(TokenKind::Number, start);
+                }
+                _ if is_ident_byte(b) => {
+                    self.ident();
+                    self.push(TokenKind::Ident, start);
+                }
+                _ => {
+                    self.pos += 1;
+                    self.push(TokenKind::Punct, start);
+                }
+            }
+        }
+    }
+
+    fn line_comment(&mut self) {
+        let start = self.pos;
+        self.pos = match self.src[start..].find('\n') {
+            Some(i) => start + i,
+            None => self.bytes.len(),
+        };
+        self.push(TokenKind::LineComment, start);
+    }
+
+    fn ident(&mut self) {
+        while self.peek(0).is_some_and(is_ident_byte) {
+            self.pos += 1;
+        }
+    }
+
+    /// Consume a string from its opening quote. Unterminated strings end at
+    /// the line break.
+    fn string(&mut self, quote: u8) {
+        self.pos += 1;
+        while let Some(b) = self.peek(0) {
+            match b {
+                b'\\' => self.pos = (self.pos + 2).min(self.bytes.len()),
+                b'\n' => return,
+                _ if b == quote => {
+                    self.pos += 1;
+                    return;
+                }
+                _ => self.pos += 1,
+            }
+        }
+    }
+
+    /// Consume a regex literal from its `/`. Returns false, consuming nothing,
+    /// if it isn't closed on the same line.
+    fn regex(&mut self) -> bool {
+        let mut end = self.pos + 1;
+        let mut in_class = false;
+        loop {
+            match self.bytes.get(end) {
+                None | Some(b'\n') => return false,
+                Some(b'\\') => end += 1,
+                Some(b'[') => in_class = true,
+                Some(b']') => in_class = false,
+                Some(b'/') if !in_class => break,
+                _ => {}
+            }
+            end += 1;
+        }
+        self.pos = end + 1;
+        self.ident();
+        true
+    }
+
+    /// Consume a template literal from its opening backtick, lexing each
+    /// `${...}` as code.
+    fn template(&mut self) {
+        let mut start = self.pos;
+        self.pos += 1;
+        while let Some(b) = self.peek(0) {
+            match b {
+                b'\\' => self.pos = (self.pos + 2).min(self.bytes.len()),
+                b'`' => {
+                    self.pos += 1;
+                    self.push(TokenKind::Template, start);
+                    return;
+                }
+                b'$' if self.peek(1) == Some(b'{') && self.depth < MAX_NESTING => {
+                    self.pos += 2;
+                    self.push(TokenKind::Template, start);
+                    self.depth += 1;
+                    self.code(true);
+                    self.depth -= 1;
+                    // Resume at the closing `}`, or stop at the end of input.
+                    start = self.pos;
+                    if self.peek(0).is_none() {
+                        return;
+                    }
+                    self.pos += 1;
+                }
+                _ => self.pos += 1,
+            }
+        }
+        self.push(TokenKind::Template, start);
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek(0).is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    /// Consume a JSX tag name like `div`, `Foo.Bar` or `svg:rect`.
+    fn jsx_name(&mut self) -> Option<&'a str> {
+        let start = self.pos;
+        while self
+            .peek(0)
+            .is_some_and(|b| is_ident_byte(b) || matches!(b, b'.' | b':' | b'-'))
+        {
+            self.pos += 1;
+        }
+        (self.pos > start).then(|| &self.src[start..self.pos])
+    }
+
+    fn punct(&mut self) {
+        let start = self.pos;
+        self.pos += 1;
+        self.push(TokenKind::Punct, start);
+    }
+
+    /// `{...}` in JSX: code up to the matching `}`.
+    fn jsx_expression(&mut self) -> bool {
+        self.punct();
+        self.code(true);
+        if self.peek(0) != Some(b'}') {
+            return false;
+        }
+        self.punct();
+        true
+    }
+
+    /// Try to lex a JSX element at `<`. On failure nothing is consumed.
+    fn jsx_element(&mut self) -> bool {
+        if !self.jsx {
+            return false;
+        }
+        let (pos, len) = (self.pos, self.tokens.len());
+        if self.nested_element() {
+            return true;
+        }
+        self.pos = pos;
+        self.tokens.truncate(len);
+        false
+    }
+
+    /// Lex a JSX element at `<`, leaving the position wherever it failed.
+    /// Failures are remembered, so a tag that is never closed is scanned to
+    /// the end of the input once rather than once per attempt.
+    fn nested_element(&mut self) -> bool {
+        let start = self.pos;
+        if self.depth >= MAX_NESTING || self.failed_jsx.contains(&start) {
+            return false;
+        }
+        self.depth += 1;
+        let lexed = self.jsx_element_inner();
+        self.depth -= 1;
+        if !lexed {
+            self.failed_jsx.insert(start);
+        }
+        lexed
+    }
+
+    fn jsx_element_inner(&mut self) -> bool {
+        self.punct();
+        let name = if self.peek(0) == Some(b'>') {
+            ""
+        } else {
+            let start = self.pos;
+            let Some(name) = self.jsx_name() else {
+                return false;
+            };
+            self.push(TokenKind::Ident, start);
+            name
+        };
+
+        // Attributes.
+        loop {
+            self.skip_whitespace();
+            match self.peek(0) {
+                Some(b'/') if self.peek(1) == Some(b'>') => {
+                    self.punct();
+                    self.punct();
+                    return true;
+                }
+                Some(b'>') => {
+                    self.punct();
+                    break;
+                }
+                Some(b'{') => {
+                    if !self.jsx_expression() {
+                        return false;
+                    }
+                }
+                _ => {
+                    let start = self.pos;
+                    if name.is_empty() || self.jsx_name().is_none() {
+                        return false;
+                    }
+                    self.push(TokenKind::Ident, start);
+                    self.skip_whitespace();
+                    if self.peek(0) != Some(b'=') {
+                        continue;
+                    }
+                    self.punct();
+                    self.skip_whitespace();
+                    match self.peek(0) {
+                        Some(quote @ (b'"' | b'\'')) => {
+                            // JSX attribute strings have no escapes and may span lines.
+                            let start = self.pos;
+                            let Some(end) = self.src[start + 1..].find(quote as char) else {
+                                return false;
+                            };
+                            self.pos = start + 1 + end + 1;
+                            self.push(TokenKind::String, start);
+                        }
+                        Some(b'{') => {
+                            if !self.jsx_expression() {
+                                return false;
+                            }
+                        }
+                        Some(b'<') => {
+                            if !self.nested_element() {
+                                return false;
+                            }
+                        }
+                        _ => return false,
+                    }
+                }
+            }
+        }
+
+        // Children, up to the closing tag.
+        loop {
+            match self.peek(0) {
+                None => return false,
+                Some(b'<') if self.peek(1) == Some(b'/') => {
+                    self.punct();
+                    self.punct();
+                    self.skip_whitespace();
+                    let start = self.pos;
+                    let closing = self.jsx_name().unwrap_or("");
+                    if closing != name {
+                        return false;
+                    }
+                    if !closing.is_empty() {
+                        self.push(TokenKind::Ident, start);
+                    }
+                    self.skip_whitespace();
+                    if self.peek(0) != Some(b'>') {
+                        return false;
+                    }
+                    self.punct();
+                    return true;
+                }
+                Some(b'<') => {
+                    if !self.nested_element() {
+                        return false;
+                    }
+                }
+                Some(b'{') => {
+                    if !self.jsx_expression() {
+                        return false;
+                    }
+                }
+                Some(_) => {
+                    let start = self.pos;
+                    self.pos = match self.src[start..].find(['<', '{']) {
+                        Some(i) => start + i,
+                        None => self.bytes.len(),
+                    };
+                    self.push(TokenKind::JsxText, start);
+                }
+            }
+        }
+    }
+}
+
+/// Tokens of `content` in source order. Whitespace is skipped. `jsx` enables
+/// JSX elements, see [`is_jsx_file`].
+pub fn tokenize(content: &str, jsx: bool) -> Vec<Token> {
+    let mut lexer = Lexer {
+        src: content,
+        bytes: content.as_bytes(),
+        pos: 0,
+        tokens: Vec::new(),
+        jsx,
+        depth: 0,
+        failed_jsx: HashSet::new(),
+    };
+    lexer.code(false);
+    lexer.tokens
+}
+
+/// `content` with every comment character replaced by a space. Line breaks
+/// are kept, so line numbers and character columns still line up.
+pub fn mask_comments(content: &str, jsx: bool) -> String {
+    let mut masked = String::with_capacity(content.len());
+    let mut last = 0;
+    for token in tokenize(content, jsx).iter().filter(|t| t.is_comment()) {
+        masked.push_str(&content[last..token.start]);
+        masked.extend(token.text(content).chars().map(|c| match c {
+            '\n' | '\r' => c,
+            _ => ' ',
+        }));
+        last = token.end;
+    }
+    masked.push_str(&content[last..]);
+    masked
+}
+
+/// The token containing byte `offset`, if it isn't whitespace.
+pub fn token_at(tokens: &[Token], offset: usize) -> Option<&Token> {
+    let index = tokens.partition_point(|t| t.end <= offset);
+    tokens.get(index).filter(|t| t.start <= offset)
+}
+
+/// Offset of the `close` punctuation matching an `open` that ends right
+/// before `from`. Brackets in strings, comments and regexes don't count.
+pub fn find_closing(
+    tokens: &[Token],
+    content: &str,
+    from: usize,
+    open: u8,
+    close: u8,
+) -> Option<usize> {
+    let first = tokens.partition_point(|t| t.start < from);
+    let mut depth = 1usize;
+    for token in &tokens[first..] {
+        if token.is_punct(content, open) {
+            depth += 1;
+        } else if token.is_punct(content, close) {
+            depth -= 1;
+            if depth == 0 {
+                return Some(token.start);
+            }
+        }
+    }
+    None
+}
+
+/// Pre-compute line offsets for O(log n) line number lookup.
+pub fn build_line_offsets(content: &str) -> Vec<usize> {
+    content
+        .char_indices()
+        .filter_map(|(i, c)| if c == '\n' { Some(i) } else { None })
+        .collect()
+}
+
+/// Convert byte offset to 1-based line number using binary search.
+/// Offsets pointing to newline characters belong to the line ending at that position.
+pub fn offset_to_line(offsets: &[usize], offset: usize) -> usize {
+    match offsets.binary_search(&offset) {
+        Ok(idx) | Err(idx) => idx + 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use TokenKind::*;
+
+    fn kinds(content: &str) -> Vec<(TokenKind, &str)> {
+        tokenize(content, true)
+            .iter()
+            .map(|t| (t.kind, t.text(content)))
+            .collect()
+    }
+
+    fn literal_text(content: &str) -> Vec<(TokenKind, &str)> {
+        kinds(content)
+            .into_iter()
+            .filter(|(k, _)| !matches!(k, Ident | Number | Punct))
+            .collect()
+    }
+
+    #[test]
+    fn strings_and_comments() {
+        assert_eq!(
+            literal_text("a = 'x // y'; /* b */ c(\"d\\\"\"); // e\nf"),
+            [
+                (String, "'x // y'"),
+                (BlockComment, "/* b */"),
+                (String, "\"d\\\"\""),
+                (LineComment, "// e"),
+            ]
+        );
+    }
+
+    #[test]
+    fn code_after_block_comment_end() {
+        let masked = mask_comments("/* a\n * b */ el.innerHTML = x;\n", false);
+        assert_eq!(masked, "    \n        el.innerHTML = x;\n");
+    }
+
+    #[test]
+    fn regex_or_division_by_previous_token() {
+        assert_eq!(
+            literal_text(r"const re = /https:\/\//g; x = a / b // c"),
+            [(Regex, r"/https:\/\//g"), (LineComment, "// c")]
+        );
+        assert_eq!(
+            literal_text("if (/[/*]/.test(s)) return /a/;"),
+            [(Regex, "/[/*]/"), (Regex, "/a/")]
+        );
+        assert_eq!(literal_text("x = (a) / 2 / (b);"), []);
+        assert_eq!(literal_text("x = y /\nz"), []);
+    }
+
+    #[test]
+    fn template_interpolation_is_code() {
+        assert_eq!(
+            kinds("`a ${ {b: '}'} } c ${`d${e}`}`"),
+            [
+                (Template, "`a ${"),
+                (Punct, "{"),
+                (Ident, "b"),
+                (Punct, ":"),
+                (String, "'}'"),
+                (Punct, "}"),
+                (Template, "} c ${"),
+                (Template, "`d${"),
+                (Ident, "e"),
+                (Template, "}`"),
+                (Template, "}`"),
+            ]
+        );
+    }
+
+    #[test]
+    fn jsx_text_and_attributes() {
+        let content = "return <p className=\"a//b\" onClick={() => go('x')}>Don't // stop {/* note */ n}</p>;";
+        assert_eq!(
+            literal_text(content),
+            [
+                (String, "\"a//b\""),
+                (String, "'x'"),
+                (JsxText, "Don't // stop "),
+                (BlockComment, "/* note */"),
+            ]
+        );
+        assert_eq!(
+            literal_text("const f = <>\n  <a href='http://x'>it's</a>\n</>;"),
+            [
+                (JsxText, "\n  "),
+                (String, "'http://x'"),
+                (JsxText, "it's"),
+                (JsxText, "\n"),
+            ]
+        );
+    }
+
+    #[test]
+    fn unclosed_tag_is_not_jsx() {
+        assert_eq!(
+            literal_text("const n = <number>value; // cast\nif (a < b) c = 'd';"),
+            [(LineComment, "// cast"), (String, "'d'")]
+        );
+    }
+
+    #[test]
+    fn no_jsx_outside_jsx_files() {
+        let content = "const a = <T>(x: T) => x; // it's\nconst b = <p>it's</p>;";
+        let literal: Vec<TokenKind> = tokenize(content, false)
+            .iter()
+            .map(|t| t.kind)
+            .filter(|k| !matches!(k, Ident | Number | Punct))
+            .collect();
+        assert_eq!(literal, [LineComment, String]);
+        assert!(is_jsx_file("/src/App.tsx"));
+        assert!(!is_jsx_file("/src/app.ts"));
+    }
+
+    #[test]
+    fn deep_unclosed_generic_does_not_overflow() {
+        let content = format!(
+            "const f = <T>(x: T) => x;\n{}// done\n",
+            "let a: Map<K> = b;\n".repeat(50_000)
+        );
+        let tokens = tokenize(&content, true);
+        assert_eq!(tokens.last().map(|t| t.kind), Some(LineComment));
+        assert!(!tokens.iter().any(|t| t.kind == JsxText));
+
+        let content = format!("x = {}", "`${".repeat(10_000));
+        tokenize(&content, true);
+    }
+
+    #[test]
+    fn token_at_finds_containing_token() {
+        let content = "a('b') // c";
+        let tokens = tokenize(content, true);
+        assert_eq!(token_at(&tokens, 3).map(|t| t.kind), Some(String));
+        assert_eq!(token_at(&tokens, 6), None);
+        assert_eq!(token_at(&tokens, 9).map(|t| t.kind), Some(LineComment));
+    }
+
+    #[test]
+    fn find_closing_skips_literal_text() {
+        let content = r"f(a, ')', /\)/, g(b) /* ) */)";
+        let tokens = tokenize(content, true);
+        assert_eq!(
+            find_closing(&tokens, content, 2, b'(', b')'),
+            Some(content.len() - 1)
+        );
+        assert_eq!(find_closing(&tokens, content, 18, b'(', b')'), Some(19));
+        assert_eq!(
+            find_closing(&tokenize("f(a", true), "f(a", 2, b'(', b')'),
+            None
+        );
+    }
+
+    #[test]
+    fn multibyte_content() {
+        let content = "const é = '日本'; // ü\n";
+        assert_eq!(mask_comments(content, true), "const é = '日本';     \n");
+    }
+
+    #[test]
+    fn unterminated_input_does_not_panic() {
+        for content in ["'abc", "`a${b", "/* x", "<div>{a", "x = /abc", "'\\"] {
+            tokenize(content, true);
+            mask_comments(content, true);
+        }
+    }
+
+    #[test]
+    fn line_offsets_work() {
+        let offsets = build_line_offsets("line1\nline2\nline3");
+        assert_eq!(offset_to_line(&offsets, 0), 1);
+        assert_eq!(offset_to_line(&offsets, 6), 2);
+        assert_eq!(offset_to_line(&offsets, 12), 3);
+    }
+}
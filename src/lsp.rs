@@ -0,0 +1,517 @@
+//! Language server over stdio publishing guardrails findings as diagnostics.
+//!
+//! Implements the small part of LSP an editor needs: full document sync,
+//! `publishDiagnostics`, hover with the fix text, and code actions that describe
+//! the fix. biome runs on open and save only; edits re-run the custom rules.
+
+use crate::config::Config;
+use crate::rules::{self, Severity, Violation};
+use crate::runner::Runner;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+// JSON-RPC error codes
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// Runners for one effective config. biome runs on open and save only.
+struct Runners {
+    config: Config,
+    full: Runner,
+    rules_only: Runner,
+}
+
+impl Runners {
+    fn new(config: Config) -> Self {
+        let mut rules_config = config.clone();
+        rules_config.disable_biome();
+        Self {
+            full: Runner::new(&config),
+            rules_only: Runner::new(&rules_config),
+            config,
+        }
+    }
+}
+
+struct Document {
+    text: String,
+    violations: Vec<Violation>,
+}
+
+pub struct Server<W: Write> {
+    out: W,
+    biome: bool,
+    runners: Vec<Runners>,
+    documents: HashMap<String, Document>,
+    shutdown: bool,
+}
+
+/// Read one `Content-Length` framed message. Returns None at end of input.
+fn read_message(input: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let length = length.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length header")
+    })?;
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn uri_to_path(uri: &str) -> Option<String> {
+    let encoded = uri.strip_prefix("file://")?;
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            // Decode bytes, not a `str` slice: `%` may precede a multi-byte character.
+            let hex = |b: u8| (b as char).to_digit(16);
+            if let (Some(high), Some(low)) = (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                decoded.push((high * 16 + low) as u8);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8(decoded).ok()
+}
+
+fn lsp_severity(severity: Severity) -> u8 {
+    match severity {
+        Severity::Critical | Severity::High => 1, // Error
+        Severity::Medium => 2,                    // Warning
+        Severity::Low => 3,                       // Information
+        Severity::Info => 4,                      // Hint
+    }
+}
+
+/// UTF-16 offset of the 1-based character `column` on the 0-based `line`.
+fn character(text: &str, line: u32, column: u32) -> usize {
+    text.lines()
+        .nth(line as usize)
+        .map(|l| {
+            l.chars()
+                .take(column.saturating_sub(1) as usize)
+                .map(char::len_utf16)
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// The violation's span, or its whole line without one. File-level violations
+/// point at the start.
+fn range(text: &str, v: &Violation) -> Value {
+    let Some(line) = v.line.and_then(|l| l.checked_sub(1)) else {
+        return json!({"start": {"line": 0, "character": 0}, "end": {"line": 0, "character": 0}});
+    };
+    if let (Some(column), Some(end_line), Some(end_column)) = (
+        v.column,
+        v.end_line.and_then(|l| l.checked_sub(1)),
+        v.end_column,
+    ) {
+        return json!({
+            "start": {"line": line, "character": character(text, line, column)},
+            "end": {"line": end_line, "character": character(text, end_line, end_column)}
+        });
+    }
+    let width = text
+        .lines()
+        .nth(line as usize)
+        .map(|l| l.encode_utf16().count())
+        .unwrap_or(0);
+    json!({
+        "start": {"line": line, "character": 0},
+        "end": {"line": line, "character": width}
+    })
+}
+
+fn diagnostic(text: &str, v: &Violation) -> Value {
+    json!({
+        "range": range(text, v),
+        "severity": lsp_severity(v.severity),
+        "code": v.rule,
+        "source": rules::source(&v.rule),
+        "message": v.failure,
+    })
+}
+
+/// Violations on the 0-based `line`, including file-level ones.
+fn violations_on_line(doc: &Document, line: u64) -> impl Iterator<Item = &Violation> {
+    doc.violations
+        .iter()
+        .filter(move |v| v.line.map_or(line == 0, |l| u64::from(l) == line + 1))
+}
+
+impl<W: Write> Server<W> {
+    pub fn new(out: W, biome: bool) -> Self {
+        Self {
+            out,
+            biome,
+            runners: Vec::new(),
+            documents: HashMap::new(),
+            shutdown: false,
+        }
+    }
+
+    fn send(&mut self, message: Value) -> io::Result<()> {
+        let body = message.to_string();
+        write!(self.out, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+        self.out.flush()
+    }
+
+    fn respond(&mut self, id: Value, result: Value) -> io::Result<()> {
+        self.send(json!({"jsonrpc": "2.0", "id": id, "result": result}))
+    }
+
+    fn publish(&mut self, uri: &str) -> io::Result<()> {
+        let diagnostics: Vec<Value> = self
+            .documents
+            .get(uri)
+            .map(|doc| {
+                doc.violations
+                    .iter()
+                    .map(|v| diagnostic(&doc.text, v))
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.send(json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": {"uri": uri, "diagnostics": diagnostics}
+        }))
+    }
+
+    fn update(&mut self, uri: &str, text: String, with_biome: bool) -> io::Result<()> {
+        let Some(path) = uri_to_path(uri) else {
+            return Ok(());
+        };
+        // Each document gets its project's config (see `Config::load_for`).
+        let mut config = Config::load_for(Path::new(&path));
+        if !self.biome {
+            config.disable_biome();
+        }
+        let index = match self.runners.iter().position(|r| r.config == config) {
+            Some(index) => index,
+            None => {
+                self.runners.push(Runners::new(config));
+                self.runners.len() - 1
+            }
+        };
+        let runners = &self.runners[index];
+        let runner = if with_biome {
+            &runners.full
+        } else {
+            &runners.rules_only
+        };
+        let violations = runner.check(&path, &text);
+        self.documents
+            .insert(uri.to_string(), Document { text, violations });
+        self.publish(uri)
+    }
+
+    fn hover(&self, params: &Value) -> Value {
+        let uri = params.pointer("/textDocument/uri").and_then(Value::as_str);
+        let line = params.pointer("/position/line").and_then(Value::as_u64);
+        let (Some(doc), Some(line)) = (uri.and_then(|u| self.documents.get(u)), line) else {
+            return Value::Null;
+        };
+        let sections: Vec<String> = violations_on_line(doc, line)
+            .map(|v| {
+                format!(
+                    "**{}** ({})\n\nfix: {}",
+                    v.rule,
+                    rules::source(&v.rule),
+                    v.failure
+                )
+            })
+            .collect();
+        if sections.is_empty() {
+            return Value::Null;
+        }
+        json!({"contents": {"kind": "markdown", "value": sections.join("\n\n---\n\n")}})
+    }
+
+    fn code_actions(&self, params: &Value) -> Value {
+        let uri = params.pointer("/textDocument/uri").and_then(Value::as_str);
+        let start = params.pointer("/range/start/line").and_then(Value::as_u64);
+        let end = params.pointer("/range/end/line").and_then(Value::as_u64);
+        let (Some(doc), Some(start), Some(end)) =
+            (uri.and_then(|u| self.documents.get(u)), start, end)
+        else {
+            return json!([]);
+        };
+        let actions: Vec<Value> = (start..=end)
+            .flat_map(|line| violations_on_line(doc, line))
+            .map(|v| {
+                json!({
+                    "title": format!("{}: {}", v.rule, v.failure),
+                    "kind": "quickfix",
+                    "diagnostics": [diagnostic(&doc.text, v)],
+                })
+            })
+            .collect();
+        Value::Array(actions)
+    }
+
+    /// Handle one message. Returns false when the server should exit.
+    pub fn handle(&mut self, message: Value) -> io::Result<bool> {
+        let method = message.get("method").and_then(Value::as_str).unwrap_or("");
+        let id = message.get("id").cloned();
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        let uri = params
+            .pointer("/textDocument/uri")
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .to_string();
+
+        match method {
+            "initialize" => self.respond(
+                id.unwrap_or(Value::Null),
+                json!({
+                    "capabilities": {
+                        "textDocumentSync": {"openClose": true, "change": 1, "save": true},
+                        "hoverProvider": true,
+                        "codeActionProvider": true
+                    },
+                    "serverInfo": {"name": "guardrails", "version": env!("CARGO_PKG_VERSION")}
+                }),
+            )?,
+            "textDocument/didOpen" => {
+                if let Some(text) = params.pointer("/textDocument/text").and_then(Value::as_str) {
+                    self.update(&uri, text.to_string(), true)?;
+                }
+            }
+            "textDocument/didChange" => {
+                // Full sync: the last change holds the whole document.
+                let text = params
+                    .pointer("/contentChanges")
+                    .and_then(Value::as_array)
+                    .and_then(|c| c.last())
+                    .and_then(|c| c.get("text"))
+                    .and_then(Value::as_str);
+                if let Some(text) = text {
+                    self.update(&uri, text.to_string(), false)?;
+                }
+            }
+            "textDocument/didSave" => {
+                if let Some(text) = self.documents.get(&uri).map(|d| d.text.clone()) {
+                    self.update(&uri, text, true)?;
+                }
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                self.publish(&uri)?;
+            }
+            "textDocument/hover" => {
+                let result = self.hover(&params);
+                self.respond(id.unwrap_or(Value::Null), result)?;
+            }
+            "textDocument/codeAction" => {
+                let result = self.code_actions(&params);
+                self.respond(id.unwrap_or(Value::Null), result)?;
+            }
+            "shutdown" => {
+                self.shutdown = true;
+                self.respond(id.unwrap_or(Value::Null), Value::Null)?;
+            }
+            "exit" => return Ok(false),
+            _ => {
+                // Requests need an answer; notifications are ignored.
+                if let Some(id) = id {
+                    self.send(json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": {"code": METHOD_NOT_FOUND, "message": format!("unsupported method: {}", method)}
+                    }))?;
+                }
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Serve on stdin/stdout until `exit`. Returns the process exit code.
+pub fn run(biome: bool) -> i32 {
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let mut server = Server::new(io::stdout(), biome);
+
+    loop {
+        let message = match read_message(&mut input) {
+            Ok(Some(m)) => m,
+            Ok(None) => break,
+            Err(e) => {
+                eprintln!("guardrails: lsp: {}", e);
+                return 1;
+            }
+        };
+        match server.handle(message) {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(e) => {
+                eprintln!("guardrails: lsp: {}", e);
+                return 1;
+            }
+        }
+    }
+
+    if server.shutdown {
+        0
+    } else {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server() -> Server<Vec<u8>> {
+        Server::new(Vec::new(), false)
+    }
+
+    /// Decode all framed messages written by the server.
+    fn sent(server: &mut Server<Vec<u8>>) -> Vec<Value> {
+        let out = std::mem::take(&mut server.out);
+        let mut input = io::BufReader::new(out.as_slice());
+        let mut messages = Vec::new();
+        while let Some(m) = read_message(&mut input).unwrap() {
+            messages.push(m);
+        }
+        messages
+    }
+
+    fn open(server: &mut Server<Vec<u8>>, text: &str) {
+        server
+            .handle(json!({
+                "jsonrpc": "2.0",
+                "method": "textDocument/didOpen",
+                "params": {"textDocument": {"uri": "file:///src/utils/a%20b.ts", "languageId": "typescript", "version": 1, "text": text}}
+            }))
+            .unwrap();
+    }
+
+    #[test]
+    fn reads_framed_messages() {
+        let body = r#"{"jsonrpc":"2.0","method":"exit"}"#;
+        let raw = format!("Content-Length: {}\r\n\r\n{}", body.len(), body);
+        let mut input = io::BufReader::new(raw.as_bytes());
+        let message = read_message(&mut input).unwrap().unwrap();
+        assert_eq!(message["method"], "exit");
+        assert!(read_message(&mut input).unwrap().is_none());
+    }
+
+    #[test]
+    fn decodes_file_uris() {
+        assert_eq!(
+            uri_to_path("file:///src/a%20b.ts").as_deref(),
+            Some("/src/a b.ts")
+        );
+        assert!(uri_to_path("untitled:Untitled-1").is_none());
+        assert_eq!(uri_to_path("file:///a%日.ts").as_deref(), Some("/a%日.ts"));
+        assert_eq!(
+            uri_to_path("file:///a%a日.ts").as_deref(),
+            Some("/a%a日.ts")
+        );
+    }
+
+    #[test]
+    fn initialize_advertises_capabilities() {
+        let mut s = server();
+        s.handle(json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}))
+            .unwrap();
+        let messages = sent(&mut s);
+        assert_eq!(messages[0]["id"], 1);
+        assert_eq!(messages[0]["result"]["capabilities"]["hoverProvider"], true);
+    }
+
+    #[test]
+    fn publishes_diagnostics_on_open_and_change() {
+        let mut s = server();
+        open(&mut s, "const a = 1;\nel.innerHTML = x;\n");
+        let messages = sent(&mut s);
+        let diagnostics = &messages[0]["params"]["diagnostics"];
+        assert_eq!(messages[0]["method"], "textDocument/publishDiagnostics");
+        assert_eq!(diagnostics[0]["code"], "security");
+        assert_eq!(diagnostics[0]["severity"], 1);
+        assert_eq!(diagnostics[0]["range"]["start"]["line"], 1);
+        assert_eq!(diagnostics[0]["range"]["start"]["character"], 2);
+        assert_eq!(diagnostics[0]["range"]["end"]["character"], 14);
+
+        s.handle(json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didChange",
+            "params": {"textDocument": {"uri": "file:///src/utils/a%20b.ts", "version": 2}, "contentChanges": [{"text": "el.textContent = x;\n"}]}
+        }))
+        .unwrap();
+        let messages = sent(&mut s);
+        assert_eq!(messages[0]["params"]["diagnostics"], json!([]));
+    }
+
+    #[test]
+    fn hover_and_code_action_show_fix() {
+        let mut s = server();
+        open(&mut s, "el.innerHTML = x;\n");
+        sent(&mut s);
+
+        let doc = json!({"uri": "file:///src/utils/a%20b.ts"});
+        s.handle(
+            json!({"jsonrpc": "2.0", "id": 2, "method": "textDocument/hover",
+            "params": {"textDocument": doc, "position": {"line": 0, "character": 3}}}),
+        )
+        .unwrap();
+        s.handle(json!({"jsonrpc": "2.0", "id": 3, "method": "textDocument/codeAction",
+            "params": {"textDocument": doc, "range": {"start": {"line": 0, "character": 0}, "end": {"line": 0, "character": 0}}, "context": {"diagnostics": []}}}))
+            .unwrap();
+        let messages = sent(&mut s);
+        let hover = messages[0]["result"]["contents"]["value"].as_str().unwrap();
+        assert!(hover.contains("textContent"));
+        let title = messages[1]["result"][0]["title"].as_str().unwrap();
+        assert!(title.starts_with("security:"));
+    }
+
+    #[test]
+    fn shutdown_then_exit() {
+        let mut s = server();
+        assert!(s
+            .handle(json!({"jsonrpc": "2.0", "id": 9, "method": "shutdown"}))
+            .unwrap());
+        assert!(s.shutdown);
+        assert!(!s
+            .handle(json!({"jsonrpc": "2.0", "method": "exit"}))
+            .unwrap());
+    }
+
+    #[test]
+    fn unknown_request_gets_error() {
+        let mut s = server();
+        s.handle(json!({"jsonrpc": "2.0", "id": 4, "method": "workspace/symbol"}))
+            .unwrap();
+        s.handle(json!({"jsonrpc": "2.0", "method": "$/cancelRequest"}))
+            .unwrap();
+        let messages = sent(&mut s);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0]["error"]["code"], METHOD_NOT_FOUND);
+    }
+}
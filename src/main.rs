@@ -1,71 +1,56 @@
+mod baseline;
 mod biome;
+mod commands;
 mod config;
+#[cfg(unix)]
+mod daemon;
+mod fingerprint;
+mod git;
+mod graph;
+mod hook;
+mod imports;
+mod input;
+mod lexer;
+mod lsp;
+mod notebook;
 mod reporter;
 mod rules;
-mod scanner;
+mod runner;
+mod shell;
+mod suppress;
 
-use config::Config;
-use reporter::{format_violations, format_warnings};
-use rules::Violation;
+use hook::{Hook, Outcome};
 use std::io::{self, Read};
 
 const MAX_INPUT_SIZE: u64 = 10_000_000; // 10MB limit
 
-fn is_js_ts_file(path: &str) -> bool {
-    path.ends_with(".ts")
-        || path.ends_with(".tsx")
-        || path.ends_with(".js")
-        || path.ends_with(".jsx")
-}
-
-#[derive(serde::Deserialize)]
-struct ToolInput {
-    tool_name: String,
-    tool_input: ToolInputData,
-}
-
-#[derive(serde::Deserialize)]
-struct ToolInputData {
-    file_path: Option<String>,
-    content: Option<String>,
-    new_string: Option<String>,
-    edits: Option<Vec<EditItem>>,
-}
-
-#[derive(serde::Deserialize)]
-struct EditItem {
-    new_string: Option<String>,
-}
-
-fn get_file_and_content(input: &ToolInput) -> Option<(String, String)> {
-    let file_path = input.tool_input.file_path.clone()?;
-
-    let content = match input.tool_name.as_str() {
-        "Write" => input.tool_input.content.clone()?,
-        "Edit" => input.tool_input.new_string.clone()?,
-        "MultiEdit" => {
-            let edits = input.tool_input.edits.as_ref()?;
-            edits
-                .iter()
-                .filter_map(|e| e.new_string.clone())
-                .collect::<Vec<_>>()
-                .join("\n")
-        }
-        _ => return None,
+/// Prefer a running daemon; check in-process if there is none (or it fails).
+/// `enabled` and `daemon` come from the payload's effective config, so project
+/// files can set them too.
+fn run_hook(input_str: &str) -> Outcome {
+    let input = match hook::parse_input(input_str) {
+        Ok(input) => input,
+        Err(outcome) => return outcome,
     };
+    let config = hook::config_for(&input);
+    if !config.enabled {
+        return Outcome::default();
+    }
 
-    if file_path.is_empty() || content.is_empty() {
-        return None;
+    #[cfg(unix)]
+    if config.daemon && std::env::var_os("GUARDRAILS_NO_DAEMON").is_none() {
+        if let Some(outcome) = daemon::request(input_str) {
+            return outcome;
+        }
     }
 
-    Some((file_path, content))
+    Hook::new(config).check(&input)
 }
 
 fn main() {
-    let config = Config::load();
-
-    if !config.enabled {
-        std::process::exit(0);
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = commands::run(&args) {
+        std::process::exit(code);
     }
 
     let mut input_str = String::new();
@@ -89,58 +74,13 @@ fn main() {
         std::process::exit(1);
     }
 
-    let input: ToolInput = match serde_json::from_str(&input_str) {
-        Ok(v) => v,
-        Err(e) => {
-            eprintln!("guardrails: invalid JSON input: {}", e);
-            std::process::exit(1);
-        }
-    };
-
-    let Some((file_path, content)) = get_file_and_content(&input) else {
-        eprintln!(
-            "guardrails: skipping {} (unsupported or empty)",
-            input.tool_name
-        );
-        std::process::exit(0);
-    };
-
-    let mut violations: Vec<Violation> = Vec::new();
-
-    if config.rules.biome && is_js_ts_file(&file_path) {
-        if biome::is_available() {
-            violations.extend(biome::check(&content, &file_path));
-        } else {
-            eprintln!("guardrails: biome not found in PATH, skipping biome checks");
-        }
-    }
-
-    let rules = rules::load_rules(&config);
-    for rule in &rules {
-        if !rule.file_pattern.is_match(&file_path) {
-            continue;
-        }
-        violations.extend(rule.check(&content, &file_path));
-    }
-
-    let blocking: Vec<&Violation> = violations
-        .iter()
-        .filter(|v| config.severity.block_on.contains(&v.severity))
-        .collect();
-
-    let warnings: Vec<&Violation> = violations
-        .iter()
-        .filter(|v| !config.severity.block_on.contains(&v.severity))
-        .collect();
+    let outcome = run_hook(&input_str);
 
-    if !warnings.is_empty() {
-        eprintln!("{}", format_warnings(&warnings));
+    if !outcome.stdout.is_empty() {
+        println!("{}", outcome.stdout);
     }
-
-    if !blocking.is_empty() {
-        eprintln!("{}", format_violations(&blocking));
-        std::process::exit(2);
+    if !outcome.stderr.is_empty() {
+        eprintln!("{}", outcome.stderr);
     }
-
-    std::process::exit(0);
+    std::process::exit(outcome.code);
 }
@@ -0,0 +1,223 @@
+//! NotebookEdit support: resolve the edited cell and the kernel language.
+//!
+//! Cells are checked as standalone files with a virtual path such as
+//! `analysis.ipynb.py`, so file-pattern based rule selection picks the rules
+//! for the cell's language. Findings are reported as `analysis.ipynb#<cell>`.
+
+use crate::input::ToolInput;
+use serde_json::Value;
+
+/// A code cell edit, ready to be checked.
+pub struct CellChange {
+    /// Path used for rule selection, e.g. `/nb/analysis.ipynb.py`.
+    pub check_path: String,
+    /// Location reported in violations, e.g. `/nb/analysis.ipynb#3f2a`.
+    pub location: String,
+    /// Current cell source when an existing cell is replaced.
+    pub before: Option<String>,
+    pub after: String,
+}
+
+fn read_notebook(path: &str) -> Option<Value> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Kernel language from notebook metadata. Defaults to Python, like Jupyter.
+fn kernel_language(notebook: Option<&Value>) -> String {
+    let metadata = notebook.and_then(|nb| nb.get("metadata"));
+    metadata
+        .and_then(|m| m.pointer("/kernelspec/language"))
+        .or_else(|| metadata.and_then(|m| m.pointer("/language_info/name")))
+        .and_then(Value::as_str)
+        .unwrap_or("python")
+        .to_lowercase()
+}
+
+fn extension_for(language: &str) -> Option<&'static str> {
+    match language {
+        "python" | "python3" => Some("py"),
+        "javascript" => Some("js"),
+        "typescript" => Some("ts"),
+        _ => None,
+    }
+}
+
+/// Cell source is stored either as a string or as a list of lines.
+fn source_text(source: &Value) -> String {
+    match source {
+        Value::String(s) => s.clone(),
+        Value::Array(lines) => lines.iter().filter_map(Value::as_str).collect(),
+        _ => String::new(),
+    }
+}
+
+/// Find a cell by its `id`, falling back to a numeric index (`3` or `cell-3`)
+/// for notebooks written before cell ids existed.
+fn find_cell<'a>(notebook: &'a Value, cell_id: &str) -> Option<&'a Value> {
+    let cells = notebook.get("cells")?.as_array()?;
+    cells
+        .iter()
+        .find(|c| c.get("id").and_then(Value::as_str) == Some(cell_id))
+        .or_else(|| {
+            let index: usize = cell_id
+                .strip_prefix("cell-")
+                .unwrap_or(cell_id)
+                .parse()
+                .ok()?;
+            cells.get(index)
+        })
+}
+
+pub fn get_cell_change(input: &ToolInput) -> Option<CellChange> {
+    let notebook = input
+        .tool_input
+        .notebook_path
+        .as_deref()
+        .and_then(read_notebook);
+    get_cell_change_with(input, notebook.as_ref())
+}
+
+fn get_cell_change_with(input: &ToolInput, notebook: Option<&Value>) -> Option<CellChange> {
+    let data = &input.tool_input;
+    let notebook_path = data.notebook_path.as_deref().filter(|p| !p.is_empty())?;
+    let edit_mode = data.edit_mode.as_deref().unwrap_or("replace");
+    if edit_mode == "delete" {
+        return None;
+    }
+    let after = data.new_source.clone().filter(|s| !s.is_empty())?;
+
+    let existing = match (edit_mode, notebook, data.cell_id.as_deref()) {
+        ("replace", Some(nb), Some(id)) => find_cell(nb, id),
+        _ => None,
+    };
+
+    let cell_type = data
+        .cell_type
+        .as_deref()
+        .or_else(|| {
+            existing
+                .and_then(|c| c.get("cell_type"))
+                .and_then(Value::as_str)
+        })
+        .unwrap_or("code");
+    if cell_type != "code" {
+        return None;
+    }
+
+    let extension = extension_for(&kernel_language(notebook))?;
+
+    let cell = match (edit_mode, data.cell_id.as_deref()) {
+        ("insert", Some(id)) => format!("new-after-{}", id),
+        ("insert", None) => "new".to_string(),
+        (_, Some(id)) => id.to_string(),
+        (_, None) => "cell".to_string(),
+    };
+
+    Some(CellChange {
+        check_path: format!("{}.{}", notebook_path, extension),
+        location: format!("{}#{}", notebook_path, cell),
+        before: existing
+            .and_then(|c| c.get("source"))
+            .map(source_text)
+            .filter(|s| !s.is_empty()),
+        after,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(tool_input: &str) -> ToolInput {
+        serde_json::from_str(&format!(
+            r#"{{"tool_name":"NotebookEdit","tool_input":{}}}"#,
+            tool_input
+        ))
+        .expect("valid tool input")
+    }
+
+    fn notebook(language: &str) -> Value {
+        serde_json::json!({
+            "metadata": {"kernelspec": {"language": language}},
+            "cells": [
+                {"id": "a1", "cell_type": "code", "source": ["x = 1\n", "print(x)"]},
+                {"id": "b2", "cell_type": "markdown", "source": "# Title"}
+            ]
+        })
+    }
+
+    #[test]
+    fn replace_code_cell() {
+        let nb = notebook("python");
+        let change = get_cell_change_with(
+            &input(r#"{"notebook_path":"/nb/a.ipynb","cell_id":"a1","new_source":"y = 2"}"#),
+            Some(&nb),
+        )
+        .unwrap();
+        assert_eq!(change.check_path, "/nb/a.ipynb.py");
+        assert_eq!(change.location, "/nb/a.ipynb#a1");
+        assert_eq!(change.before.as_deref(), Some("x = 1\nprint(x)"));
+        assert_eq!(change.after, "y = 2");
+    }
+
+    #[test]
+    fn uses_kernel_language() {
+        let nb = notebook("typescript");
+        let change = get_cell_change_with(
+            &input(r#"{"notebook_path":"/nb/a.ipynb","cell_id":"a1","new_source":"let x = 1;"}"#),
+            Some(&nb),
+        )
+        .unwrap();
+        assert_eq!(change.check_path, "/nb/a.ipynb.ts");
+    }
+
+    #[test]
+    fn insert_has_no_before() {
+        let nb = notebook("python");
+        let change = get_cell_change_with(
+            &input(
+                r#"{"notebook_path":"/nb/a.ipynb","cell_id":"a1","new_source":"z","cell_type":"code","edit_mode":"insert"}"#,
+            ),
+            Some(&nb),
+        )
+        .unwrap();
+        assert_eq!(change.location, "/nb/a.ipynb#new-after-a1");
+        assert!(change.before.is_none());
+    }
+
+    #[test]
+    fn skips_markdown_delete_and_unknown_kernels() {
+        let nb = notebook("python");
+        let markdown = r##"{"notebook_path":"/nb/a.ipynb","cell_id":"b2","new_source":"# New"}"##;
+        assert!(get_cell_change_with(&input(markdown), Some(&nb)).is_none());
+
+        let delete = r#"{"notebook_path":"/nb/a.ipynb","cell_id":"a1","edit_mode":"delete"}"#;
+        assert!(get_cell_change_with(&input(delete), Some(&nb)).is_none());
+
+        let r_nb = notebook("R");
+        let replace = r#"{"notebook_path":"/nb/a.ipynb","cell_id":"a1","new_source":"x <- 1"}"#;
+        assert!(get_cell_change_with(&input(replace), Some(&r_nb)).is_none());
+    }
+
+    #[test]
+    fn finds_cells_by_index_without_ids() {
+        let nb = serde_json::json!({"cells": [{"cell_type": "code", "source": "a"}]});
+        let change = get_cell_change_with(
+            &input(r#"{"notebook_path":"/nb/a.ipynb","cell_id":"cell-0","new_source":"b"}"#),
+            Some(&nb),
+        )
+        .unwrap();
+        assert_eq!(change.before.as_deref(), Some("a"));
+    }
+
+    #[test]
+    fn new_notebook_defaults_to_python() {
+        let change = get_cell_change_with(
+            &input(r#"{"notebook_path":"/nb/new.ipynb","new_source":"x = 1"}"#),
+            None,
+        )
+        .unwrap();
+        assert_eq!(change.check_path, "/nb/new.ipynb.py");
+    }
+}
@@ -1,4 +1,8 @@
-use crate::rules::Violation;
+use crate::config::{Config, Decision};
+use crate::rules::{self, Violation};
+use crate::suppress::Suppression;
+use serde::Serialize;
+use std::collections::HashMap;
 
 fn format_rule_name(rule: &str) -> (String, &'static str) {
     if rule.starts_with("biome/") {
@@ -6,11 +10,202 @@ fn format_rule_name(rule: &str) -> (String, &'static str) {
         let name = short.rsplit('/').next().unwrap_or(short);
         (name.to_string(), "biome")
     } else {
-        (rule.to_string(), "guardrails")
+        (rule.to_string(), rules::source(rule))
     }
 }
 
-pub fn format_violations(violations: &[&Violation]) -> String {
+/// Violations grouped by the decision the severity policy assigns them.
+pub struct Report<'a> {
+    pub deny: Vec<&'a Violation>,
+    pub ask: Vec<&'a Violation>,
+    pub allow: Vec<&'a Violation>,
+    /// Findings hidden by inline directives; listed, never blocking.
+    pub suppressed: Vec<&'a Suppression>,
+    /// Occurrences left out of the lists above by `maxOccurrences`.
+    pub omitted: usize,
+    /// Content the violations point into, for code frames.
+    pub source: Option<&'a str>,
+    /// `codeFrame.maxWidth`, or None when code frames are off.
+    pub frame_width: Option<usize>,
+}
+
+/// Keep the first `max` occurrences of each rule in each file. Returns how many were dropped.
+fn cap_occurrences(violations: &mut Vec<&Violation>, max: usize) -> usize {
+    if max == 0 {
+        return 0;
+    }
+    let before = violations.len();
+    let mut seen: HashMap<(&str, &str), usize> = HashMap::new();
+    violations.retain(|v| {
+        let count = seen.entry((&v.file, &v.rule)).or_default();
+        *count += 1;
+        *count <= max
+    });
+    before - violations.len()
+}
+
+impl<'a> Report<'a> {
+    pub fn new(violations: &'a [Violation], config: &Config) -> Self {
+        Self::with_decisions(violations, config, |v| config.decision(v))
+    }
+
+    /// Like `new`, with each violation's decision from `decide`, e.g. the
+    /// config of the project its file is in.
+    pub fn with_decisions(
+        violations: &'a [Violation],
+        config: &Config,
+        decide: impl Fn(&Violation) -> Decision,
+    ) -> Self {
+        let mut report = Report {
+            deny: Vec::new(),
+            ask: Vec::new(),
+            allow: Vec::new(),
+            suppressed: Vec::new(),
+            omitted: 0,
+            source: None,
+            frame_width: config
+                .code_frame
+                .enabled
+                .then_some(config.code_frame.max_width),
+        };
+        for v in violations {
+            match decide(v) {
+                Decision::Deny => report.deny.push(v),
+                Decision::Ask => report.ask.push(v),
+                Decision::Allow => report.allow.push(v),
+            }
+        }
+        for list in [&mut report.deny, &mut report.ask, &mut report.allow] {
+            report.omitted += cap_occurrences(list, config.max_occurrences);
+        }
+        report
+    }
+
+    pub fn with_suppressed(mut self, suppressed: &'a [Suppression]) -> Self {
+        self.suppressed.extend(suppressed);
+        self
+    }
+
+    /// Show code frames from `content`, the single file the violations are in.
+    pub fn with_source(mut self, content: &'a str) -> Self {
+        self.source = Some(content);
+        self
+    }
+
+    fn frame(&self, v: &Violation) -> Vec<String> {
+        match (self.source, self.frame_width) {
+            (Some(content), Some(width)) => code_frame(content, v, width),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// The violation's line with one line of context on each side, numbered, and
+/// carets under its span (or the whole line without a column). Lines wider
+/// than `max_width` characters are cut to a window around the span.
+fn code_frame(content: &str, v: &Violation, max_width: usize) -> Vec<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let Some(index) = v
+        .line
+        .and_then(|l| (l as usize).checked_sub(1))
+        .filter(|&i| i < lines.len())
+    else {
+        return Vec::new();
+    };
+    let first = index.saturating_sub(1);
+    let last = (index + 1).min(lines.len() - 1);
+    let number_width = (last + 1).to_string().len();
+    let max_width = max_width.max(1);
+
+    // Caret range in characters, 0-based and end-exclusive.
+    let text = lines[index];
+    let len = text.chars().count();
+    let (start, end) = match v.column {
+        Some(column) => {
+            let start = (column as usize).saturating_sub(1).min(len);
+            let end = match (v.end_line, v.end_column) {
+                (Some(end_line), Some(end_column)) if end_line == index as u32 + 1 => {
+                    (end_column as usize).saturating_sub(1).min(len)
+                }
+                _ => len,
+            };
+            (start, end.max(start + 1))
+        }
+        None => {
+            let indent = text.chars().take_while(|c| c.is_whitespace()).count();
+            (indent, len.max(indent + 1))
+        }
+    };
+    // Scroll right only when the span would start off screen.
+    let skip = if end > max_width {
+        start - start.min(max_width / 4)
+    } else {
+        0
+    };
+
+    let excerpt = |line: &str| {
+        let chars: Vec<char> = line
+            .chars()
+            .map(|c| if c == '\t' { ' ' } else { c })
+            .collect();
+        let mut out = String::new();
+        if skip > 0 && chars.len() > skip {
+            out.push('…');
+        }
+        out.extend(chars.iter().skip(skip).take(max_width));
+        if chars.len() > skip + max_width {
+            out.push('…');
+        }
+        out
+    };
+
+    let mut frame = Vec::new();
+    for (i, line) in lines.iter().enumerate().take(last + 1).skip(first) {
+        let marker = if i == index { ">" } else { " " };
+        let row = format!(
+            "    {} {:>width$} | {}",
+            marker,
+            i + 1,
+            excerpt(line),
+            width = number_width
+        );
+        frame.push(row.trim_end().to_string());
+        if i == index {
+            let offset = start - skip + usize::from(skip > 0);
+            let carets = end.min(skip + max_width) - start;
+            frame.push(format!(
+                "      {:>width$} | {}{}",
+                "",
+                " ".repeat(offset),
+                "^".repeat(carets.max(1)),
+                width = number_width
+            ));
+        }
+    }
+    frame
+}
+
+fn format_location(v: &Violation) -> String {
+    match (v.line, v.column) {
+        (Some(l), Some(c)) => format!("{}:{}:{}", v.file, l, c),
+        (Some(l), None) => format!("{}:{}", v.file, l),
+        _ => v.file.clone(),
+    }
+}
+
+fn push_numbered(lines: &mut Vec<String>, violations: &[&Violation], report: &Report) {
+    for (i, v) in violations.iter().enumerate() {
+        let (rule_name, source) = format_rule_name(&v.rule);
+        lines.push(format!("[{}] {} ({})", i + 1, rule_name, source));
+        lines.push(format!("    location: {}", format_location(v)));
+        lines.extend(report.frame(v));
+        lines.push(format!("    fix: {}", v.failure));
+        lines.push(String::new());
+    }
+}
+
+pub fn format_violations(report: &Report) -> String {
+    let violations = &report.deny;
     if violations.is_empty() {
         return String::new();
     }
@@ -23,20 +218,28 @@ pub fn format_violations(violations: &[&Violation]) -> String {
         String::new(),
     ];
 
-    for (i, v) in violations.iter().enumerate() {
-        let (rule_name, source) = format_rule_name(&v.rule);
-        let location = match v.line {
-            Some(l) => format!("{}:{}", v.file, l),
-            None => v.file.clone(),
-        };
+    push_numbered(&mut lines, violations, report);
+    lines.push("Fix the issues above and retry.".to_string());
 
-        lines.push(format!("[{}] {} ({})", i + 1, rule_name, source));
-        lines.push(format!("    location: {}", location));
-        lines.push(format!("    fix: {}", v.failure));
-        lines.push(String::new());
+    lines.join("\n")
+}
+
+pub fn format_asks(report: &Report) -> String {
+    let violations = &report.ask;
+    if violations.is_empty() {
+        return String::new();
     }
 
-    lines.push("Fix the issues above and retry.".to_string());
+    let mut lines = vec![
+        format!(
+            "GUARDRAILS: {} issues need approval for this operation",
+            violations.len()
+        ),
+        String::new(),
+    ];
+
+    push_numbered(&mut lines, violations, report);
+    lines.push("Approve to proceed anyway, or deny and fix the issues above.".to_string());
 
     lines.join("\n")
 }
@@ -50,14 +253,371 @@ pub fn format_warnings(violations: &[&Violation]) -> String {
 
     for v in violations {
         let (rule_name, source) = format_rule_name(&v.rule);
-        let location = match v.line {
-            Some(l) => format!("{}:{}", v.file, l),
-            None => v.file.clone(),
-        };
-        lines.push(format!("  - {} ({}) at {}", rule_name, source, location));
+        lines.push(format!(
+            "  - {} ({}) at {}",
+            rule_name,
+            source,
+            format_location(v)
+        ));
+    }
+
+    lines.push(String::new());
+
+    lines.join("\n")
+}
+
+pub fn format_suppressions(suppressed: &[&Suppression]) -> String {
+    if suppressed.is_empty() {
+        return String::new();
+    }
+
+    let mut lines = vec![format!(
+        "GUARDRAILS: {} findings suppressed by inline directives",
+        suppressed.len()
+    )];
+
+    for s in suppressed {
+        let (rule_name, source) = format_rule_name(&s.violation.rule);
+        lines.push(format!(
+            "  - {} ({}) at {} -- {}",
+            rule_name,
+            source,
+            format_location(&s.violation),
+            s.reason
+        ));
     }
 
     lines.push(String::new());
 
     lines.join("\n")
 }
+
+/// Note for the occurrences `maxOccurrences` left out.
+pub fn format_omitted(omitted: usize) -> String {
+    if omitted == 0 {
+        return String::new();
+    }
+    format!(
+        "GUARDRAILS: {} more occurrences of the rules above not shown (see maxOccurrences)",
+        omitted
+    )
+}
+
+/// Combined report for `guardrails check`.
+pub fn format_check_report(report: &Report, files_checked: usize) -> String {
+    let mut lines = Vec::new();
+
+    if !report.deny.is_empty() {
+        lines.push(format!("GUARDRAILS: {} errors", report.deny.len()));
+        lines.push(String::new());
+        push_numbered(&mut lines, &report.deny, report);
+    }
+    if !report.ask.is_empty() {
+        lines.push(format!(
+            "GUARDRAILS: {} issues that need approval",
+            report.ask.len()
+        ));
+        lines.push(String::new());
+        push_numbered(&mut lines, &report.ask, report);
+    }
+    if !report.allow.is_empty() {
+        lines.push(format_warnings(&report.allow));
+    }
+    if !report.suppressed.is_empty() {
+        lines.push(format_suppressions(&report.suppressed));
+    }
+    if report.omitted > 0 {
+        lines.push(format_omitted(report.omitted));
+    }
+
+    let mut summary = format!(
+        "{} files checked: {} errors, {} need approval, {} warnings",
+        files_checked,
+        report.deny.len(),
+        report.ask.len(),
+        report.allow.len()
+    );
+    if !report.suppressed.is_empty() {
+        summary.push_str(&format!(", {} suppressed", report.suppressed.len()));
+    }
+    lines.push(summary);
+
+    lines.join("\n")
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HookOutput {
+    hook_specific_output: HookSpecificOutput,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HookSpecificOutput {
+    hook_event_name: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    permission_decision: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    permission_decision_reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    additional_context: Option<String>,
+}
+
+/// Warnings and suppressions, shown to the model without affecting the decision.
+fn additional_context(report: &Report) -> Option<String> {
+    let sections: Vec<String> = [
+        format_warnings(&report.allow),
+        format_suppressions(&report.suppressed),
+        format_omitted(report.omitted),
+    ]
+    .into_iter()
+    .filter(|s| !s.is_empty())
+    .collect();
+    (!sections.is_empty()).then(|| sections.join("\n"))
+}
+
+/// Build the PreToolUse hook JSON for stdout. Returns None when there is nothing to report.
+/// Deny wins over ask. Without either no decision is set, leaving Claude Code's own
+/// permission flow in charge instead of auto-approving the tool call.
+pub fn format_hook_output(report: &Report) -> Option<String> {
+    if report.deny.is_empty()
+        && report.ask.is_empty()
+        && report.allow.is_empty()
+        && report.suppressed.is_empty()
+    {
+        return None;
+    }
+
+    let (decision, reason) = if !report.deny.is_empty() {
+        let mut reason = format_violations(report);
+        if !report.ask.is_empty() {
+            reason.push_str("\n\n");
+            reason.push_str(&format_asks(report));
+        }
+        (Some("deny"), Some(reason))
+    } else if !report.ask.is_empty() {
+        (Some("ask"), Some(format_asks(report)))
+    } else {
+        (None, None)
+    };
+
+    let output = HookOutput {
+        hook_specific_output: HookSpecificOutput {
+            hook_event_name: "PreToolUse",
+            permission_decision: decision,
+            permission_decision_reason: reason,
+            additional_context: additional_context(report),
+        },
+    };
+
+    serde_json::to_string(&output).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Severity;
+
+    fn violation(rule: &str, severity: Severity) -> Violation {
+        Violation {
+            rule: rule.to_string(),
+            severity,
+            failure: "fix it".to_string(),
+            file: "/src/a.ts".to_string(),
+            line: Some(3),
+            column: None,
+            end_line: None,
+            end_column: None,
+        }
+    }
+
+    fn parse(output: &str) -> serde_json::Value {
+        serde_json::from_str(output).expect("valid JSON")
+    }
+
+    fn report<'a>(
+        deny: &[&'a Violation],
+        ask: &[&'a Violation],
+        allow: &[&'a Violation],
+    ) -> Report<'a> {
+        Report {
+            deny: deny.to_vec(),
+            ask: ask.to_vec(),
+            allow: allow.to_vec(),
+            suppressed: Vec::new(),
+            omitted: 0,
+            source: None,
+            frame_width: None,
+        }
+    }
+
+    #[test]
+    fn report_groups_by_decision() {
+        let config: Config =
+            serde_json::from_str(r#"{"severity": {"askOn": ["medium"]}}"#).unwrap();
+        let violations = [
+            violation("security", Severity::High),
+            violation("dom-access", Severity::Medium),
+            violation("flaky-test", Severity::Low),
+        ];
+        let report = Report::new(&violations, &config);
+        assert_eq!(report.deny.len(), 1);
+        assert_eq!(report.ask.len(), 1);
+        assert_eq!(report.allow.len(), 1);
+    }
+
+    #[test]
+    fn report_caps_occurrences_per_rule_and_file() {
+        let config: Config = serde_json::from_str(r#"{"maxOccurrences": 2}"#).unwrap();
+        let mut violations = vec![violation("security", Severity::High); 4];
+        violations.push(violation("crypto-weak", Severity::High));
+        violations[3].file = "/src/b.ts".to_string();
+        let report = Report::new(&violations, &config);
+        assert_eq!(report.deny.len(), 4);
+        assert_eq!(report.omitted, 1);
+        assert!(format_check_report(&report, 2).contains("1 more occurrences"));
+
+        let config: Config = serde_json::from_str(r#"{"maxOccurrences": 0}"#).unwrap();
+        assert_eq!(Report::new(&violations, &config).omitted, 0);
+    }
+
+    #[test]
+    fn code_frame_marks_span() {
+        let content = "a();\nconst h = md5(x);\n\tb();\n";
+        let mut v = violation("crypto-weak", Severity::High);
+        v.line = Some(2);
+        v.column = Some(11);
+        v.end_line = Some(2);
+        v.end_column = Some(14);
+        assert_eq!(
+            code_frame(content, &v, 100),
+            [
+                "      1 | a();",
+                "    > 2 | const h = md5(x);",
+                "        |           ^^^",
+                "      3 |  b();",
+            ]
+        );
+
+        v.column = None;
+        v.line = Some(1);
+        assert_eq!(
+            code_frame(content, &v, 100),
+            [
+                "    > 1 | a();",
+                "        | ^^^^",
+                "      2 | const h = md5(x);"
+            ]
+        );
+
+        v.line = Some(9);
+        assert!(code_frame(content, &v, 100).is_empty());
+    }
+
+    #[test]
+    fn code_frame_cuts_long_lines_around_span() {
+        let content = format!("{}el.innerHTML = x;{}", "a".repeat(50), "b".repeat(50));
+        let mut v = violation("security", Severity::High);
+        v.line = Some(1);
+        v.column = Some(53);
+        v.end_line = Some(1);
+        v.end_column = Some(65);
+        assert_eq!(
+            code_frame(&content, &v, 20),
+            [
+                "    > 1 | …aaael.innerHTML = x;…",
+                "        |       ^^^^^^^^^^^^",
+            ]
+        );
+    }
+
+    #[test]
+    fn location_includes_column() {
+        let mut v = violation("security", Severity::High);
+        assert_eq!(format_location(&v), "/src/a.ts:3");
+        v.column = Some(7);
+        assert_eq!(format_location(&v), "/src/a.ts:3:7");
+    }
+
+    #[test]
+    fn hook_output_denies_blocking() {
+        let v = violation("security", Severity::High);
+        let w = violation("sync-io", Severity::Medium);
+        let json = parse(&format_hook_output(&report(&[&v], &[], &[&w])).unwrap());
+        let out = &json["hookSpecificOutput"];
+        assert_eq!(out["hookEventName"], "PreToolUse");
+        assert_eq!(out["permissionDecision"], "deny");
+        assert!(out["permissionDecisionReason"]
+            .as_str()
+            .unwrap()
+            .contains("security"));
+        assert!(out["additionalContext"]
+            .as_str()
+            .unwrap()
+            .contains("sync-io"));
+    }
+
+    #[test]
+    fn hook_output_asks() {
+        let a = violation("dom-access", Severity::Medium);
+        let json = parse(&format_hook_output(&report(&[], &[&a], &[])).unwrap());
+        let out = &json["hookSpecificOutput"];
+        assert_eq!(out["permissionDecision"], "ask");
+        assert!(out["permissionDecisionReason"]
+            .as_str()
+            .unwrap()
+            .contains("need approval"));
+    }
+
+    #[test]
+    fn hook_output_deny_includes_asks() {
+        let v = violation("security", Severity::High);
+        let a = violation("dom-access", Severity::Medium);
+        let json = parse(&format_hook_output(&report(&[&v], &[&a], &[])).unwrap());
+        let out = &json["hookSpecificOutput"];
+        assert_eq!(out["permissionDecision"], "deny");
+        assert!(out["permissionDecisionReason"]
+            .as_str()
+            .unwrap()
+            .contains("dom-access"));
+    }
+
+    #[test]
+    fn hook_output_warnings_only_has_no_decision() {
+        let w = violation("sync-io", Severity::Medium);
+        let json = parse(&format_hook_output(&report(&[], &[], &[&w])).unwrap());
+        let out = &json["hookSpecificOutput"];
+        assert!(out.get("permissionDecision").is_none());
+        assert!(out["additionalContext"].is_string());
+    }
+
+    #[test]
+    fn hook_output_lists_suppressions() {
+        let suppressed = [Suppression {
+            violation: violation("sync-io", Severity::High),
+            reason: "startup only".to_string(),
+        }];
+        let report = report(&[], &[], &[]).with_suppressed(&suppressed);
+        let json = parse(&format_hook_output(&report).unwrap());
+        let context = json["hookSpecificOutput"]["additionalContext"]
+            .as_str()
+            .unwrap();
+        assert!(context.contains("1 findings suppressed"));
+        assert!(context.contains("-- startup only"));
+    }
+
+    #[test]
+    fn check_report_summarizes() {
+        let v = violation("security", Severity::High);
+        let w = violation("sync-io", Severity::Medium);
+        let output = format_check_report(&report(&[&v], &[], &[&w]), 12);
+        assert!(output.contains("[1] security (guardrails)"));
+        assert!(output.contains("sync-io"));
+        assert!(output.ends_with("12 files checked: 1 errors, 0 need approval, 1 warnings"));
+    }
+
+    #[test]
+    fn hook_output_empty() {
+        assert!(format_hook_output(&report(&[], &[], &[])).is_none());
+    }
+}
@@ -1,9 +1,15 @@
-use super::{find_non_comment_match, Rule, Severity, Violation, RE_JS_FILE};
+use super::{Category, Example, Rule, RuleMeta, Setting, Severity, Violation, JS_TS, RE_JS_FILE};
+use crate::config::{glob_set, ArchitectureConfig};
+use crate::imports::{self, Resolver, Target};
+use globset::GlobSet;
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
 
 struct LayerViolation {
     from_pattern: &'static Lazy<Regex>,
+    /// Matched against the import target (see `import_target`).
     importing: &'static Lazy<Regex>,
     failure: &'static str,
 }
@@ -16,15 +22,13 @@ static RE_COMPONENTS: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"/components/").expect("RE_COMPONENTS: invalid regex"));
 
 static RE_IMPORT_UI: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r#"from\s+['"].*/(components|hooks|pages|features)/"#)
-        .expect("RE_IMPORT_UI: invalid regex")
+    Regex::new(r"/(components|hooks|pages|features)/").expect("RE_IMPORT_UI: invalid regex")
 });
 static RE_IMPORT_UI_NO_FEATURES: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r#"from\s+['"].*/(components|hooks|pages)/"#)
-        .expect("RE_IMPORT_UI_NO_FEATURES: invalid regex")
+    Regex::new(r"/(components|hooks|pages)/").expect("RE_IMPORT_UI_NO_FEATURES: invalid regex")
 });
 static RE_IMPORT_PAGES: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r#"from\s+['"].*\/pages/"#).expect("RE_IMPORT_PAGES: invalid regex"));
+    Lazy::new(|| Regex::new(r"/pages/").expect("RE_IMPORT_PAGES: invalid regex"));
 
 static LAYER_VIOLATIONS: Lazy<[LayerViolation; 3]> = Lazy::new(|| {
     [
@@ -46,29 +50,198 @@ static LAYER_VIOLATIONS: Lazy<[LayerViolation; 3]> = Lazy::new(|| {
     ]
 });
 
-pub fn rule() -> Rule {
-    Rule {
-        file_pattern: RE_JS_FILE.clone(),
-        checker: Box::new(|content: &str, file_path: &str| {
-            let mut result = Vec::new();
+struct Layers {
+    names: Vec<String>,
+    files: Vec<GlobSet>,
+    allow: Vec<Vec<String>>,
+}
 
-            for v in LAYER_VIOLATIONS.iter() {
-                if !v.from_pattern.is_match(file_path) {
-                    continue;
-                }
-                if let Some(line_num) = find_non_comment_match(content, v.importing) {
-                    result.push(Violation {
-                        rule: "architecture".to_string(),
-                        severity: Severity::High,
-                        failure: v.failure.to_string(),
-                        file: file_path.to_string(),
-                        line: Some(line_num),
-                    });
+impl Layers {
+    fn new(config: &ArchitectureConfig) -> Self {
+        for layer in &config.layers {
+            for name in &layer.allow {
+                if !config.layers.iter().any(|l| &l.name == name) {
+                    eprintln!(
+                        "guardrails: warning: architecture layer {:?} allows unknown layer {:?}",
+                        layer.name, name
+                    );
                 }
             }
+        }
+        Self {
+            names: config.layers.iter().map(|l| l.name.clone()).collect(),
+            files: config.layers.iter().map(|l| glob_set(&l.files)).collect(),
+            allow: config.layers.iter().map(|l| l.allow.clone()).collect(),
+        }
+    }
 
-            result
-        }),
+    /// First layer whose globs match `path`.
+    fn layer_of(&self, path: &Path) -> Option<usize> {
+        self.files.iter().position(|f| f.is_match(path))
+    }
+
+    fn check(&self, resolver: &Resolver, content: &str, file_path: &str) -> Vec<Violation> {
+        let from_path = imports::absolute(file_path);
+        let Some(from) = self.layer_of(&from_path) else {
+            return Vec::new();
+        };
+
+        let mut result = Vec::new();
+        for import in imports::parse(content, file_path) {
+            let Target::File(target) = resolver.resolve(&from_path, &import.specifier) else {
+                continue;
+            };
+            let Some(to) = self.layer_of(&target) else {
+                continue;
+            };
+            if to == from || self.allow[from].contains(&self.names[to]) {
+                continue;
+            }
+            let allowed = if self.allow[from].is_empty() {
+                "none".to_string()
+            } else {
+                self.allow[from].join(", ")
+            };
+            result.push(Violation {
+                rule: META.id.to_string(),
+                severity: Severity::High,
+                failure: format!(
+                    "Layer '{}' must not import '{}' ({}). Allowed layers: {}",
+                    self.names[from], self.names[to], import.specifier, allowed
+                ),
+                file: file_path.to_string(),
+                line: Some(import.span.line),
+                column: Some(import.span.column),
+                end_line: Some(import.span.end_line),
+                end_column: Some(import.span.end_column),
+            });
+        }
+        result
+    }
+}
+
+/// The import target below the directory it shares with the importing file,
+/// as `/components/Button/`, so directories above the project never match.
+/// Unresolved specifiers are used as written.
+fn import_target(resolver: &Resolver, from: &Path, specifier: &str) -> String {
+    let Target::File(target) = resolver.resolve(from, specifier) else {
+        return format!("/{}/", specifier);
+    };
+    let common: PathBuf = from
+        .components()
+        .zip(target.components())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a)
+        .collect();
+    let tail = target.strip_prefix(&common).unwrap_or(&target);
+    let parts: Vec<_> = tail
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect();
+    format!("/{}/", parts.join("/"))
+}
+
+fn check_builtin(resolver: &Resolver, content: &str, file_path: &str) -> Vec<Violation> {
+    let from = imports::absolute(file_path);
+    let mut imports = None;
+    let mut result = Vec::new();
+
+    for v in LAYER_VIOLATIONS.iter() {
+        if !v.from_pattern.is_match(file_path) {
+            continue;
+        }
+        let targets = imports.get_or_insert_with(|| {
+            imports::parse(content, file_path)
+                .into_iter()
+                .map(|i| (i.span, import_target(resolver, &from, &i.specifier)))
+                .collect::<Vec<_>>()
+        });
+        for (span, _) in targets.iter().filter(|(_, t)| v.importing.is_match(t)) {
+            result.push(Violation {
+                rule: META.id.to_string(),
+                severity: Severity::High,
+                failure: v.failure.to_string(),
+                file: file_path.to_string(),
+                line: Some(span.line),
+                column: Some(span.column),
+                end_line: Some(span.end_line),
+                end_column: Some(span.end_column),
+            });
+        }
+    }
+
+    result
+}
+
+pub static META: RuleMeta = RuleMeta {
+    id: Cow::Borrowed("architecture"),
+    aliases: &[],
+    category: Category::Architecture,
+    severity: Severity::High,
+    languages: JS_TS,
+    checks_content: true,
+    description: Cow::Borrowed(
+        "Flags imports that cross layer boundaries: the built-in utils/services/components checks, or the layers configured under `architecture.layers`.",
+    ),
+    rationale: "Imports that point the wrong way between layers tangle the codebase: UI code leaks into utilities, and services can no longer be used or tested without the UI.",
+    disable_when: "Small projects, monoliths, or scripts",
+    settings: &[
+        Setting {
+            key: "architecture.layers",
+            description: "Replace the built-in checks with your own layers and the layers each may import",
+        },
+    ],
+    examples: &[
+        Example {
+            path: "/src/utils/formatter.ts",
+            code: r#"import { Button } from '../components/Button';"#,
+            flagged: true,
+        },
+        Example {
+            path: "/src/components/Calendar.tsx",
+            code: r#"import { format } from '../utils/date';"#,
+            flagged: false,
+        },
+        Example {
+            path: "/src/components/Header.tsx",
+            code: r#"import { HomePage } from '../pages/Home';"#,
+            flagged: true,
+        },
+        Example {
+            path: "/src/pages/Home.tsx",
+            code: r#"import { Button } from '../components/Button';"#,
+            flagged: false,
+        },
+    ],
+};
+
+pub struct ArchitectureRule {
+    resolver: Resolver,
+    /// None for the built-in checks.
+    layers: Option<Layers>,
+}
+
+impl Rule for ArchitectureRule {
+    fn meta(&self) -> &RuleMeta {
+        &META
+    }
+
+    fn applies_to(&self, file_path: &str) -> bool {
+        RE_JS_FILE.is_match(file_path)
+    }
+
+    fn check(&self, content: &str, file_path: &str) -> Vec<Violation> {
+        match &self.layers {
+            Some(layers) => layers.check(&self.resolver, content, file_path),
+            None => check_builtin(&self.resolver, content, file_path),
+        }
+    }
+}
+
+pub fn rule(config: &ArchitectureConfig) -> ArchitectureRule {
+    ArchitectureRule {
+        resolver: Resolver::default(),
+        layers: (!config.layers.is_empty()).then(|| Layers::new(config)),
     }
 }
 
@@ -77,7 +250,7 @@ mod tests {
     use super::*;
 
     fn check(content: &str, path: &str) -> Vec<Violation> {
-        rule().check(content, path)
+        rule(&ArchitectureConfig::default()).check(content, path)
     }
 
     #[test]
@@ -90,6 +263,24 @@ mod tests {
             .contains("utils should not depend on UI"));
     }
 
+    #[test]
+    fn reports_every_offending_import_with_its_span() {
+        let content = "import { Button } from '../components/Button';\nimport { format } from './date';\nimport { Modal } from '../components/Modal';\nconst Icon = require('../components/Icon');\n";
+        let violations = check(content, "/src/utils/formatter.ts");
+        let spans: Vec<_> = violations
+            .iter()
+            .map(|v| (v.line, v.column, v.end_column))
+            .collect();
+        assert_eq!(
+            spans,
+            [
+                (Some(1), Some(25), Some(45)),
+                (Some(3), Some(24), Some(43)),
+                (Some(4), Some(23), Some(41)),
+            ]
+        );
+    }
+
     #[test]
     fn detects_services_importing_ui() {
         let content = r#"import { useAuth } from '../hooks/useAuth';"#;
@@ -135,4 +326,86 @@ mod tests {
             );
         }
     }
+
+    fn layered() -> ArchitectureConfig {
+        serde_json::from_str(
+            r#"{"layers": [
+                {"name": "domain", "files": ["src/domain/**"]},
+                {"name": "application", "files": ["src/application/**"], "allow": ["domain"]},
+                {"name": "infrastructure", "files": ["src/infrastructure/**"], "allow": ["domain", "application"]},
+                {"name": "ui", "files": ["src/ui/**", "src/features/*/ui/**"], "allow": ["application", "domain"]}
+            ]}"#,
+        )
+        .unwrap()
+    }
+
+    fn write(dir: &Path, name: &str, content: &str) {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn configured_layers_resolve_relative_imports() {
+        let rule = rule(&layered());
+        let content =
+            "import { User } from '../domain/user';\nimport { db } from '../infrastructure/db';\n";
+        let violations = rule.check(content, "/repo/src/ui/Profile.tsx");
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].line, Some(2));
+        assert_eq!(violations[0].column, Some(21));
+        assert_eq!(violations[0].end_column, Some(41));
+        assert!(violations[0]
+            .failure
+            .contains("Layer 'ui' must not import 'infrastructure'"));
+
+        let content = "import { save } from '../application/save';\n";
+        let violations = rule.check(content, "/repo/src/domain/user.ts");
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].failure.contains("Allowed layers: none"));
+    }
+
+    #[test]
+    fn configured_layers_replace_builtin_checks() {
+        let content = r#"import { Button } from '../components/Button';"#;
+        assert!(rule(&layered())
+            .check(content, "/src/utils/formatter.ts")
+            .is_empty());
+    }
+
+    #[test]
+    fn resolves_tsconfig_path_aliases() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "tsconfig.base.json",
+            "{\n  // shared\n  \"compilerOptions\": {\"paths\": {\"@/*\": [\"src/*\"],},},\n}\n",
+        );
+        write(root, "tsconfig.json", r#"{"extends": "./tsconfig.base"}"#);
+
+        let file = root.join("src/features/cart/ui/Cart.tsx");
+        let content = "import { repo } from '@/infrastructure/cart-repo';\nimport { Cart } from '@/domain/cart';\nconst x = await import('@/infrastructure/lazy');\n";
+        let violations = rule(&layered()).check(content, &file.to_string_lossy());
+        let lines: Vec<_> = violations.iter().map(|v| v.line).collect();
+        assert_eq!(lines, [Some(1), Some(3)]);
+    }
+
+    #[test]
+    fn builtin_checks_use_resolved_targets() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("components/app");
+        write(
+            &root,
+            "tsconfig.json",
+            r#"{"compilerOptions": {"paths": {"@/*": ["src/*"]}}}"#,
+        );
+        let file = root.join("src/utils/format.ts");
+        let file = file.to_string_lossy();
+
+        let violations = check("import { Button } from '@/components/Button';\n", &file);
+        assert_eq!(violations.len(), 1);
+        // The repo itself living under a `components/` directory is not a UI import.
+        assert!(check("import { x } from '../helpers/x';\n", &file).is_empty());
+    }
 }
@@ -1,89 +1,158 @@
-use super::{find_non_comment_match, Rule, Severity, Violation, RE_JS_FILE};
+use super::{Category, Example, Rule, RuleMeta, Severity, Violation, JS_TS, RE_JS_FILE};
+use crate::imports::{self, Import, ImportKind, Resolver, Target};
 use once_cell::sync::Lazy;
-use regex::Regex;
+use std::borrow::Cow;
+
+/// How a package is pulled in whole.
+#[derive(Clone, Copy)]
+enum Form {
+    /// `import _ from 'lodash'` or `require('lodash')`
+    Default,
+    /// `import * as Icons from '@mui/icons-material'`
+    Namespace,
+}
 
 struct LargeImport {
-    pattern: &'static Lazy<Regex>,
     package: &'static str,
+    form: Form,
     suggestion: &'static str,
 }
 
-static RE_LODASH_FULL: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r#"import\s+[\w]+\s+from\s+['"]lodash['"]"#).expect("RE_LODASH_FULL: invalid regex")
-});
-
-static RE_MOMENT_FULL: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r#"import\s+[\w]+\s+from\s+['"]moment['"]"#).expect("RE_MOMENT_FULL: invalid regex")
-});
-
-static RE_MUI_ICONS_FULL: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r#"import\s+\*\s+as\s+[\w]+\s+from\s+['"]@mui/icons-material['"]"#)
-        .expect("RE_MUI_ICONS_FULL: invalid regex")
-});
-
-static RE_DATE_FNS_FULL: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r#"import\s+\*\s+as\s+[\w]+\s+from\s+['"]date-fns['"]"#)
-        .expect("RE_DATE_FNS_FULL: invalid regex")
-});
-
-static RE_RXJS_FULL: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r#"import\s+\*\s+as\s+[\w]+\s+from\s+['"]rxjs['"]"#)
-        .expect("RE_RXJS_FULL: invalid regex")
-});
+impl LargeImport {
+    fn matches(&self, import: &Import) -> bool {
+        match self.form {
+            Form::Default => {
+                import.default_binding().is_some() || import.kind == ImportKind::Require
+            }
+            Form::Namespace => import.is_namespace(),
+        }
+    }
+}
 
 static LARGE_IMPORTS: Lazy<[LargeImport; 5]> = Lazy::new(|| {
     [
         LargeImport {
-            pattern: &RE_LODASH_FULL,
             package: "lodash",
+            form: Form::Default,
             suggestion: "Use 'lodash-es' with tree-shaking or import specific functions: import { map } from 'lodash-es'",
         },
         LargeImport {
-            pattern: &RE_MOMENT_FULL,
             package: "moment",
+            form: Form::Default,
             suggestion: "Use 'date-fns' or 'dayjs' instead (moment is deprecated and large)",
         },
         LargeImport {
-            pattern: &RE_MUI_ICONS_FULL,
             package: "@mui/icons-material",
+            form: Form::Namespace,
             suggestion: "Import specific icons: import { Home } from '@mui/icons-material'",
         },
         LargeImport {
-            pattern: &RE_DATE_FNS_FULL,
             package: "date-fns",
+            form: Form::Namespace,
             suggestion: "Import specific functions: import { format } from 'date-fns'",
         },
         LargeImport {
-            pattern: &RE_RXJS_FULL,
             package: "rxjs",
+            form: Form::Namespace,
             suggestion: "Import specific operators: import { map } from 'rxjs/operators'",
         },
     ]
 });
 
-pub fn rule() -> Rule {
-    Rule {
-        file_pattern: RE_JS_FILE.clone(),
-        checker: Box::new(|content: &str, file_path: &str| {
-            let mut violations = Vec::new();
-
-            for import in LARGE_IMPORTS.iter() {
-                if let Some(line_num) = find_non_comment_match(content, import.pattern) {
-                    violations.push(Violation {
-                        rule: "bundle-size".to_string(),
-                        severity: Severity::Medium,
-                        failure: format!(
-                            "Full {} import increases bundle size. {}",
-                            import.package, import.suggestion
-                        ),
-                        file: file_path.to_string(),
-                        line: Some(line_num),
-                    });
-                }
+pub static META: RuleMeta = RuleMeta {
+    id: Cow::Borrowed("bundle-size"),
+    aliases: &["bundleSize"],
+    category: Category::Performance,
+    severity: Severity::Medium,
+    languages: JS_TS,
+    checks_content: true,
+    description: Cow::Borrowed(
+        "Flags whole-package imports of large libraries (`lodash`, `moment`, `@mui/icons-material`, `date-fns`, `rxjs`).",
+    ),
+    rationale: "Importing a whole library ships all of it to the browser, including the parts that are never used.",
+    disable_when: "Backend/Node.js (no bundle size concerns)",
+    settings: &[],
+    examples: &[
+        Example {
+            path: "/src/utils/list.ts",
+            code: "import _ from 'lodash';",
+            flagged: true,
+        },
+        Example {
+            path: "/src/utils/list.ts",
+            code: "import map from 'lodash/map';",
+            flagged: false,
+        },
+        Example {
+            path: "/src/icons.ts",
+            code: r#"import * as Icons from '@mui/icons-material';"#,
+            flagged: true,
+        },
+        Example {
+            path: "/src/icons.ts",
+            code: r#"import { Home } from '@mui/icons-material';"#,
+            flagged: false,
+        },
+    ],
+};
+
+pub struct BundleSizeRule {
+    resolver: Resolver,
+}
+
+impl Rule for BundleSizeRule {
+    fn meta(&self) -> &RuleMeta {
+        &META
+    }
+
+    fn applies_to(&self, file_path: &str) -> bool {
+        RE_JS_FILE.is_match(file_path)
+    }
+
+    fn check(&self, content: &str, file_path: &str) -> Vec<Violation> {
+        let from = imports::absolute(file_path);
+        // Path aliases can shadow package names, so only real packages count.
+        let found: Vec<Import> = imports::parse(content, file_path)
+            .into_iter()
+            .filter(|i| {
+                matches!(
+                    self.resolver.resolve(&from, &i.specifier),
+                    Target::Package(_)
+                )
+            })
+            .collect();
+
+        let mut violations = Vec::new();
+
+        for large in LARGE_IMPORTS.iter() {
+            // Only the package root is the full bundle; `lodash/map` is fine.
+            let imports = found
+                .iter()
+                .filter(|i| i.specifier == large.package && large.matches(i));
+            for import in imports {
+                violations.push(Violation {
+                    rule: META.id.to_string(),
+                    severity: Severity::Medium,
+                    failure: format!(
+                        "Full {} import increases bundle size. {}",
+                        large.package, large.suggestion
+                    ),
+                    file: file_path.to_string(),
+                    line: Some(import.line),
+                    column: None,
+                    end_line: None,
+                    end_column: None,
+                });
             }
+        }
 
-            violations
-        }),
+        violations
+    }
+}
+
+pub fn rule() -> BundleSizeRule {
+    BundleSizeRule {
+        resolver: Resolver::default(),
     }
 }
 
@@ -150,4 +219,17 @@ mod tests {
         "#;
         assert!(check(content).is_empty());
     }
+
+    #[test]
+    fn detects_multiline_and_require_imports() {
+        let content = "const _ = require('lodash');\nimport * as\n  Rx from 'rxjs';\n";
+        let violations = check(content);
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[1].line, Some(2));
+    }
+
+    #[test]
+    fn allows_subpath_imports() {
+        assert!(check("import map from 'lodash/map';\n").is_empty());
+    }
 }
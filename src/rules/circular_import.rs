@@ -0,0 +1,212 @@
+use super::{Category, Example, Rule, RuleMeta, Severity, Violation, JS_TS, RE_JS_FILE};
+use crate::baseline::{relative_path, repo_root};
+use crate::graph::{self, project_imports, Graph};
+use crate::imports::{self, Resolver};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub static META: RuleMeta = RuleMeta {
+    id: Cow::Borrowed("circular-import"),
+    aliases: &["circularImport"],
+    category: Category::Architecture,
+    severity: Severity::High,
+    languages: JS_TS,
+    checks_content: true,
+    description: Cow::Borrowed(
+        "Flags a new runtime import that closes a cycle in the repository's import graph.",
+    ),
+    rationale: "Import cycles leave values undefined at load time, depending on which module loads first, and tie modules together so neither can change alone.",
+    disable_when: "Codebases that rely on cycles (e.g. lazy access)",
+    settings: &[],
+    examples: &[
+        Example {
+            path: "/src/services/user.ts",
+            code: "// src/hooks/useUser.ts: import { fetchUser } from '../services/user';\nimport { useUser } from '../hooks/useUser';",
+            flagged: true,
+        },
+        Example {
+            path: "/src/services/user.ts",
+            code: "// src/hooks/useUser.ts: import { fetchUser } from '../services/user';\nimport type { User } from '../hooks/useUser';",
+            flagged: false,
+        },
+    ],
+};
+
+/// Keeps import graphs by repo root, shared by every check of one runner.
+pub struct CircularImportRule {
+    resolver: Resolver,
+    graphs: Mutex<HashMap<PathBuf, Graph>>,
+    cache_path: fn(&Path) -> Option<PathBuf>,
+}
+
+impl Rule for CircularImportRule {
+    fn meta(&self) -> &RuleMeta {
+        &META
+    }
+
+    fn applies_to(&self, file_path: &str) -> bool {
+        RE_JS_FILE.is_match(file_path)
+    }
+
+    fn check(&self, content: &str, file_path: &str) -> Vec<Violation> {
+        let file = imports::absolute(file_path);
+        let Some(root) = file.parent().and_then(repo_root) else {
+            return Vec::new();
+        };
+        let Some(relative) = relative_path(&root, &file) else {
+            return Vec::new();
+        };
+        let imports = project_imports(&root, &self.resolver, &file, content);
+        if imports.is_empty() {
+            return Vec::new();
+        }
+
+        let mut graphs = self.graphs.lock().unwrap_or_else(|e| e.into_inner());
+        let graph = graphs
+            .entry(root.clone())
+            .or_insert_with(|| Graph::load(&root, (self.cache_path)(&root)));
+
+        let mut violations = Vec::new();
+        for (line, target) in imports {
+            if target == relative {
+                continue;
+            }
+            // The graph holds this file's on-disk imports, but the search
+            // stops on reaching it, so only the new content's edges count.
+            let Some(path) = graph.path(&self.resolver, &target, &relative) else {
+                continue;
+            };
+            let cycle: Vec<&str> = std::iter::once(relative.as_str())
+                .chain(path.iter().map(String::as_str))
+                .collect();
+            violations.push(Violation {
+                rule: META.id.to_string(),
+                severity: Severity::High,
+                failure: format!(
+                    "Import creates a cycle: {}. Move the shared code into a module both can import, or pass it in as a parameter",
+                    cycle.join(" -> ")
+                ),
+                file: file_path.to_string(),
+                line: Some(line),
+                column: None,
+                end_line: None,
+                end_column: None,
+            });
+        }
+        graph.save_if_changed();
+        violations
+    }
+}
+
+fn with_cache(cache_path: fn(&Path) -> Option<PathBuf>) -> CircularImportRule {
+    CircularImportRule {
+        resolver: Resolver::default(),
+        graphs: Mutex::new(HashMap::new()),
+        cache_path,
+    }
+}
+
+pub fn rule() -> CircularImportRule {
+    with_cache(graph::cache_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, content: &str) {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn check(root: &Path, name: &str, content: &str) -> Vec<Violation> {
+        let path = root.join(name);
+        with_cache(|_| None).check(content, &path.to_string_lossy())
+    }
+
+    fn repo() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir(root.join(".git")).unwrap();
+        write(
+            root,
+            "src/hooks/useUser.ts",
+            "import { fetchUser } from '../services/user';\n",
+        );
+        write(
+            root,
+            "src/services/user.ts",
+            "export const fetchUser = () => {};\n",
+        );
+        write(
+            root,
+            "src/services/session.ts",
+            "import { fetchUser } from './user';\n",
+        );
+        dir
+    }
+
+    #[test]
+    fn detects_cycle_with_path() {
+        let dir = repo();
+        let content =
+            "import { x } from './session';\nimport { useUser } from '../hooks/useUser';\n";
+        let violations = check(dir.path(), "src/services/user.ts", content);
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[1].line, Some(2));
+        assert!(violations[1]
+            .failure
+            .contains("src/services/user.ts -> src/hooks/useUser.ts -> src/services/user.ts"));
+        assert!(violations[0]
+            .failure
+            .contains("src/services/user.ts -> src/services/session.ts -> src/services/user.ts"));
+    }
+
+    #[test]
+    fn examples_match_rule() {
+        for example in META.examples {
+            let dir = tempfile::tempdir().unwrap();
+            let root = dir.path();
+            std::fs::create_dir(root.join(".git")).unwrap();
+            let (other, code) = example
+                .code
+                .lines()
+                .next()
+                .and_then(|l| l.strip_prefix("// "))
+                .and_then(|l| l.split_once(": "))
+                .unwrap();
+            write(root, other, code);
+            let path = example.path.trim_start_matches('/');
+            write(root, path, "");
+            let found = !check(root, path, example.code).is_empty();
+            assert_eq!(found, example.flagged, "{:?}", example.code);
+        }
+    }
+
+    #[test]
+    fn allows_acyclic_and_type_only_imports() {
+        let dir = repo();
+        let root = dir.path();
+        assert!(check(
+            root,
+            "src/hooks/useSession.ts",
+            "import { x } from '../services/session';\n"
+        )
+        .is_empty());
+        assert!(check(
+            root,
+            "src/services/user.ts",
+            "import type { Hook } from '../hooks/useUser';\n"
+        )
+        .is_empty());
+        assert!(check(
+            root,
+            "src/services/user.ts",
+            "const m = await import('../hooks/useUser');\n"
+        )
+        .is_empty());
+    }
+}
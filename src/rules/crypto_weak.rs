@@ -1,8 +1,15 @@
-use super::{find_non_comment_match, Rule, Severity, Violation, RE_JS_FILE};
+use super::{
+    find_non_comment_matches, Category, Example, Rule, RuleMeta, Setting, Severity, Violation,
+    JS_TS, RE_JS_FILE,
+};
+use crate::config::RuleSetting;
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::borrow::Cow;
 
 struct WeakCrypto {
+    /// Key for per-pattern settings under `rules.cryptoWeak.patterns`.
+    id: &'static str,
     pattern: &'static Lazy<Regex>,
     algorithm: &'static str,
     suggestion: &'static str,
@@ -31,21 +38,25 @@ static RE_RC4: Lazy<Regex> = Lazy::new(|| {
 static WEAK_CRYPTO: Lazy<[WeakCrypto; 4]> = Lazy::new(|| {
     [
         WeakCrypto {
+            id: "md5",
             pattern: &RE_MD5,
             algorithm: "MD5",
             suggestion: "Use SHA-256 or SHA-3 instead",
         },
         WeakCrypto {
+            id: "sha1",
             pattern: &RE_SHA1,
             algorithm: "SHA-1",
             suggestion: "Use SHA-256 or SHA-3 instead",
         },
         WeakCrypto {
+            id: "des",
             pattern: &RE_DES,
             algorithm: "DES",
             suggestion: "Use AES-256 instead",
         },
         WeakCrypto {
+            id: "rc4",
             pattern: &RE_RC4,
             algorithm: "RC4",
             suggestion: "Use AES-256 instead",
@@ -53,29 +64,88 @@ static WEAK_CRYPTO: Lazy<[WeakCrypto; 4]> = Lazy::new(|| {
     ]
 });
 
-pub fn rule() -> Rule {
-    Rule {
-        file_pattern: RE_JS_FILE.clone(),
-        checker: Box::new(|content: &str, file_path: &str| {
-            let mut violations = Vec::new();
-
-            for crypto in WEAK_CRYPTO.iter() {
-                if let Some(line_num) = find_non_comment_match(content, crypto.pattern) {
-                    violations.push(Violation {
-                        rule: "crypto-weak".to_string(),
-                        severity: Severity::High,
-                        failure: format!(
-                            "{} is cryptographically weak. {}",
-                            crypto.algorithm, crypto.suggestion
-                        ),
-                        file: file_path.to_string(),
-                        line: Some(line_num),
-                    });
-                }
+pub static META: RuleMeta = RuleMeta {
+    id: Cow::Borrowed("crypto-weak"),
+    aliases: &["cryptoWeak"],
+    category: Category::Security,
+    severity: Severity::High,
+    languages: JS_TS,
+    checks_content: true,
+    description: Cow::Borrowed("Flags weak hashes and ciphers: MD5, SHA-1, DES and RC4."),
+    rationale: "MD5 and SHA-1 collisions are practical and DES and RC4 are broken, so they protect neither passwords, signatures nor data.",
+    disable_when: "Legacy system maintenance with known constraints",
+    settings: &[
+        Setting {
+            key: "rules.cryptoWeak.patterns.<id>",
+            description: "Turn off or re-rate one algorithm: md5, sha1, des, rc4",
+        },
+    ],
+    examples: &[
+        Example {
+            path: "/src/utils/hash.ts",
+            code: "const hash = crypto.createHash('md5').update(data).digest('hex');",
+            flagged: true,
+        },
+        Example {
+            path: "/src/utils/hash.ts",
+            code: "const hash = crypto.createHash('sha256').update(data).digest('hex');",
+            flagged: false,
+        },
+        Example {
+            path: "/src/utils/hash.ts",
+            code: "const hash = crypto.createHash('sha1');",
+            flagged: true,
+        },
+    ],
+};
+
+pub struct CryptoWeakRule {
+    setting: RuleSetting,
+}
+
+impl Rule for CryptoWeakRule {
+    fn meta(&self) -> &RuleMeta {
+        &META
+    }
+
+    fn applies_to(&self, file_path: &str) -> bool {
+        RE_JS_FILE.is_match(file_path)
+    }
+
+    /// Applies the per-pattern settings itself, so load_rules doesn't wrap it.
+    fn check(&self, content: &str, file_path: &str) -> Vec<Violation> {
+        let mut violations = Vec::new();
+
+        for crypto in WEAK_CRYPTO.iter() {
+            let Some(severity) = self.setting.pattern_severity(crypto.id, Severity::High) else {
+                continue;
+            };
+            for span in find_non_comment_matches(content, file_path, crypto.pattern) {
+                violations.push(Violation {
+                    rule: META.id.to_string(),
+                    severity,
+                    failure: format!(
+                        "{} is cryptographically weak. {}",
+                        crypto.algorithm, crypto.suggestion
+                    ),
+                    file: file_path.to_string(),
+                    line: Some(span.line),
+                    column: Some(span.column),
+                    end_line: Some(span.end_line),
+                    end_column: Some(span.end_column),
+                });
             }
+        }
 
-            violations
-        }),
+        violations
+    }
+}
+
+pub fn rule(setting: &RuleSetting) -> CryptoWeakRule {
+    let ids: Vec<&str> = WEAK_CRYPTO.iter().map(|c| c.id).collect();
+    setting.warn_unknown_patterns("cryptoWeak", &ids);
+    CryptoWeakRule {
+        setting: setting.clone(),
     }
 }
 
@@ -84,7 +154,7 @@ mod tests {
     use super::*;
 
     fn check(content: &str) -> Vec<Violation> {
-        rule().check(content, "/src/utils/hash.ts")
+        rule(&RuleSetting::default()).check(content, "/src/utils/hash.ts")
     }
 
     #[test]
@@ -124,4 +194,16 @@ mod tests {
         "#;
         assert!(check(content).is_empty());
     }
+
+    #[test]
+    fn pattern_can_be_turned_off() {
+        let setting: RuleSetting =
+            serde_json::from_str(r#"{"patterns": {"md5": false, "sha1": "info"}}"#).unwrap();
+        let check = |content| rule(&setting).check(content, "/src/utils/hash.ts");
+        assert!(check("crypto.createHash('md5')").is_empty());
+        assert_eq!(
+            check("crypto.createHash('sha1')")[0].severity,
+            Severity::Info
+        );
+    }
 }
@@ -0,0 +1,200 @@
+use super::{
+    find_non_comment_match, find_non_comment_matches, Category, Language, Rule, RuleMeta, Span,
+    Violation, JS_TS, RE_JS_FILE,
+};
+use crate::config::{glob_set, CustomRule};
+use globset::GlobSet;
+use regex::Regex;
+use std::borrow::Cow;
+
+fn compile(rule_id: &str, patterns: &[String]) -> Option<Vec<Regex>> {
+    patterns
+        .iter()
+        .map(|p| match Regex::new(p) {
+            Ok(re) => Some(re),
+            Err(e) => {
+                eprintln!(
+                    "guardrails: warning: custom rule {:?} skipped, invalid regex {:?}: {}",
+                    rule_id, p, e
+                );
+                None
+            }
+        })
+        .collect()
+}
+
+/// A `customRules` entry with its regexes compiled.
+pub struct CustomRegexRule {
+    meta: RuleMeta,
+    files: Option<GlobSet>,
+    patterns: Vec<Regex>,
+    requires: Vec<Regex>,
+    unless: Vec<Regex>,
+}
+
+impl Rule for CustomRegexRule {
+    fn meta(&self) -> &RuleMeta {
+        &self.meta
+    }
+
+    fn applies_to(&self, file_path: &str) -> bool {
+        match &self.files {
+            Some(files) => files.is_match(file_path),
+            None => RE_JS_FILE.is_match(file_path),
+        }
+    }
+
+    fn check(&self, content: &str, file_path: &str) -> Vec<Violation> {
+        if self
+            .requires
+            .iter()
+            .any(|re| find_non_comment_match(content, file_path, re).is_none())
+        {
+            return Vec::new();
+        }
+        if self
+            .unless
+            .iter()
+            .any(|re| find_non_comment_match(content, file_path, re).is_some())
+        {
+            return Vec::new();
+        }
+
+        let mut spans: Vec<Span> = self
+            .patterns
+            .iter()
+            .flat_map(|re| find_non_comment_matches(content, file_path, re))
+            .collect();
+        spans.sort_by_key(|s| (s.line, s.column));
+        // Patterns matching the same text report it once.
+        spans.dedup_by(|next, prev| next.line == prev.line && next.column < prev.end_column);
+
+        spans
+            .into_iter()
+            .map(|span| Violation {
+                rule: self.meta.id.to_string(),
+                severity: self.meta.severity,
+                failure: self.meta.description.to_string(),
+                file: file_path.to_string(),
+                line: Some(span.line),
+                column: Some(span.column),
+                end_line: Some(span.end_line),
+                end_column: Some(span.end_column),
+            })
+            .collect()
+    }
+}
+
+/// Compile a `customRules` entry. Returns None (with a warning) if a regex is invalid.
+pub fn rule(custom: &CustomRule) -> Option<CustomRegexRule> {
+    let patterns = compile(&custom.id, &custom.patterns)?;
+    let requires = compile(&custom.id, &custom.requires)?;
+    let unless = compile(&custom.id, &custom.unless)?;
+    if super::is_known_rule(&custom.id) {
+        eprintln!(
+            "guardrails: warning: custom rule {:?} shares its id with a built-in rule",
+            custom.id
+        );
+    }
+
+    Some(CustomRegexRule {
+        meta: RuleMeta {
+            id: Cow::Owned(custom.id.clone()),
+            aliases: &[],
+            category: Category::Correctness,
+            severity: custom.severity,
+            languages: if custom.files.is_empty() {
+                JS_TS
+            } else {
+                &[Language::Any]
+            },
+            checks_content: true,
+            description: Cow::Owned(custom.fix.clone()),
+            rationale: "",
+            disable_when: "",
+            settings: &[],
+            examples: &[],
+        },
+        files: (!custom.files.is_empty()).then(|| glob_set(&custom.files)),
+        patterns,
+        requires,
+        unless,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Severity;
+
+    fn custom(json: &str) -> CustomRule {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn check(json: &str, content: &str, path: &str) -> Vec<Violation> {
+        let rule = rule(&custom(json)).unwrap();
+        if !rule.applies_to(path) {
+            return Vec::new();
+        }
+        rule.check(content, path)
+    }
+
+    const NO_AXIOS: &str = r#"{
+        "id": "no-axios",
+        "patterns": ["from\\s+['\"]axios['\"]", "require\\(['\"]axios['\"]\\)"],
+        "fix": "Use the shared http client in src/lib/http.ts"
+    }"#;
+
+    #[test]
+    fn detects_each_pattern() {
+        let content = "import axios from 'axios';\nconst a = require('axios');\n";
+        let violations = check(NO_AXIOS, content, "/src/api.ts");
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].rule, "no-axios");
+        assert_eq!(violations[0].severity, Severity::Medium);
+        assert_eq!(violations[1].line, Some(2));
+        assert!(violations[0].failure.contains("http client"));
+    }
+
+    #[test]
+    fn skips_comments_and_non_js_files() {
+        assert!(check(NO_AXIOS, "// import axios from 'axios';\n", "/src/api.ts").is_empty());
+        assert!(check(NO_AXIOS, "import axios from 'axios';\n", "/src/notes.md").is_empty());
+    }
+
+    #[test]
+    fn non_js_files_are_matched_line_by_line() {
+        let json = r#"{"id": "no-latest", "files": ["*.yml"], "patterns": [":latest"], "fix": "Pin the tag"}"#;
+        let content =
+            "image: node:latest\nother: a/*b\nimage2: node:latest\nurl: http://x // y:latest\n";
+        let lines: Vec<_> = check(json, content, "/repo/compose.yml")
+            .iter()
+            .map(|v| v.line)
+            .collect();
+        assert_eq!(lines, [Some(1), Some(3), Some(4)]);
+    }
+
+    #[test]
+    fn requires_and_unless_gate_the_rule() {
+        let json = r#"{
+            "id": "query-needs-tenant",
+            "files": ["src/db/**"],
+            "patterns": ["\\.query\\("],
+            "requires": ["from ['\"]pg['\"]"],
+            "unless": ["tenantId"],
+            "severity": "high",
+            "fix": "Scope queries by tenantId"
+        }"#;
+        let pg = "import { Pool } from 'pg';\npool.query(sql);\n";
+        assert_eq!(check(json, pg, "/repo/src/db/users.ts").len(), 1);
+        assert!(check(json, pg, "/repo/src/api/users.ts").is_empty());
+        assert!(check(json, "pool.query(sql);\n", "/repo/src/db/users.ts").is_empty());
+        let scoped = format!("{}const tenantId = t;\n", pg);
+        assert!(check(json, &scoped, "/repo/src/db/users.ts").is_empty());
+    }
+
+    #[test]
+    fn invalid_regex_skips_rule() {
+        assert!(rule(&custom(r#"{"id": "x", "patterns": ["("], "fix": "f"}"#)).is_none());
+    }
+}
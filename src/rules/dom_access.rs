@@ -1,6 +1,9 @@
-use super::{find_non_comment_match, Rule, Severity, Violation};
+use super::{
+    find_non_comment_matches, Category, Example, Rule, RuleMeta, Severity, Violation, JS_TS,
+};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::borrow::Cow;
 
 static RE_REACT_FILE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"\.(tsx|jsx)$").expect("RE_REACT_FILE: invalid regex"));
@@ -55,39 +58,90 @@ static DOM_ACCESS: Lazy<[DomAccess; 5]> = Lazy::new(|| {
     ]
 });
 
-pub fn rule() -> Rule {
-    Rule {
-        file_pattern: RE_REACT_FILE.clone(),
-        checker: Box::new(|content: &str, file_path: &str| {
-            let mut violations = Vec::new();
-
-            for access in DOM_ACCESS.iter() {
-                if let Some(line_num) = find_non_comment_match(content, access.pattern) {
-                    violations.push(Violation {
-                        rule: "dom-access".to_string(),
-                        severity: Severity::Medium,
-                        failure: format!(
-                            "Avoid {} in React. Use useRef or React state instead.",
-                            access.method
-                        ),
-                        file: file_path.to_string(),
-                        line: Some(line_num),
-                    });
-                }
+pub static META: RuleMeta = RuleMeta {
+    id: Cow::Borrowed("dom-access"),
+    aliases: &["domAccess"],
+    category: Category::Correctness,
+    severity: Severity::Medium,
+    languages: JS_TS,
+    checks_content: true,
+    description: Cow::Borrowed(
+        "Flags direct DOM access (`document.getElementById`, `querySelector`, `createElement`, `appendChild`) in React components.",
+    ),
+    rationale: "React owns the DOM it renders. Changing it directly bypasses reconciliation, and the change is lost or conflicts on the next render.",
+    disable_when: "Non-React projects, or vanilla JS/TS",
+    settings: &[],
+    examples: &[
+        Example {
+            path: "/src/components/App.tsx",
+            code: r#"const el = document.getElementById('root');"#,
+            flagged: true,
+        },
+        Example {
+            path: "/src/components/App.tsx",
+            code: "const ref = useRef<HTMLDivElement>(null);",
+            flagged: false,
+        },
+        Example {
+            path: "/src/components/App.tsx",
+            code: r#"const div = document.createElement('div');"#,
+            flagged: true,
+        },
+        Example {
+            path: "/src/utils/dom.ts",
+            code: r#"const el = document.getElementById('root');"#,
+            flagged: false,
+        },
+    ],
+};
+
+pub struct DomAccessRule;
+
+impl Rule for DomAccessRule {
+    fn meta(&self) -> &RuleMeta {
+        &META
+    }
+
+    fn applies_to(&self, file_path: &str) -> bool {
+        RE_REACT_FILE.is_match(file_path)
+    }
+
+    fn check(&self, content: &str, file_path: &str) -> Vec<Violation> {
+        let mut violations = Vec::new();
+
+        for access in DOM_ACCESS.iter() {
+            for span in find_non_comment_matches(content, file_path, access.pattern) {
+                violations.push(Violation {
+                    rule: META.id.to_string(),
+                    severity: Severity::Medium,
+                    failure: format!(
+                        "Avoid {} in React. Use useRef or React state instead.",
+                        access.method
+                    ),
+                    file: file_path.to_string(),
+                    line: Some(span.line),
+                    column: Some(span.column),
+                    end_line: Some(span.end_line),
+                    end_column: Some(span.end_column),
+                });
             }
+        }
 
-            violations
-        }),
+        violations
     }
 }
 
+pub fn rule() -> DomAccessRule {
+    DomAccessRule
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     fn check(content: &str, path: &str) -> Vec<Violation> {
         let r = rule();
-        if !r.file_pattern.is_match(path) {
+        if !r.applies_to(path) {
             return Vec::new();
         }
         r.check(content, path)
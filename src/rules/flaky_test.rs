@@ -1,6 +1,10 @@
-use super::{find_non_comment_match, Rule, Severity, Violation, RE_TEST_FILE};
+use super::{
+    find_non_comment_matches, Category, Example, Rule, RuleMeta, Severity, Violation, JS_TS,
+    RE_TEST_FILE,
+};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::borrow::Cow;
 
 struct FlakyPattern {
     pattern: &'static Lazy<Regex>,
@@ -53,32 +57,75 @@ static FLAKY_PATTERNS: Lazy<[FlakyPattern; 5]> = Lazy::new(|| {
     ]
 });
 
-pub fn rule() -> Rule {
-    Rule {
-        file_pattern: RE_TEST_FILE.clone(),
-        checker: Box::new(|content: &str, file_path: &str| {
-            let mut violations = Vec::new();
-
-            for pattern in FLAKY_PATTERNS.iter() {
-                if let Some(line_num) = find_non_comment_match(content, pattern.pattern) {
-                    violations.push(Violation {
-                        rule: "flaky-test".to_string(),
-                        severity: Severity::Low,
-                        failure: format!(
-                            "{} can cause flaky tests. {}",
-                            pattern.name, pattern.reason
-                        ),
-                        file: file_path.to_string(),
-                        line: Some(line_num),
-                    });
-                }
+pub static META: RuleMeta = RuleMeta {
+    id: Cow::Borrowed("flaky-test"),
+    aliases: &["flakyTest"],
+    category: Category::Testing,
+    severity: Severity::Low,
+    languages: JS_TS,
+    checks_content: true,
+    description: Cow::Borrowed(
+        "Flags timing and randomness in tests (`setTimeout`, `sleep`, `Math.random`, `Date.now`, `new Date()`) that make them flaky.",
+    ),
+    rationale: "Real timers, randomness and the current time make a test pass or fail depending on the machine and the moment it runs.",
+    disable_when: "Intentional timing/randomness tests",
+    settings: &[],
+    examples: &[
+        Example {
+            path: "/src/utils.test.ts",
+            code: r#"it('test', () => { const value = Math.random(); });"#,
+            flagged: true,
+        },
+        Example {
+            path: "/src/utils.test.ts",
+            code: r#"it('advances', () => { jest.advanceTimersByTime(1000); });"#,
+            flagged: false,
+        },
+        Example {
+            path: "/src/utils.test.ts",
+            code: r#"beforeEach(() => { jest.useFakeTimers(); });"#,
+            flagged: false,
+        },
+    ],
+};
+
+pub struct FlakyTestRule;
+
+impl Rule for FlakyTestRule {
+    fn meta(&self) -> &RuleMeta {
+        &META
+    }
+
+    fn applies_to(&self, file_path: &str) -> bool {
+        RE_TEST_FILE.is_match(file_path)
+    }
+
+    fn check(&self, content: &str, file_path: &str) -> Vec<Violation> {
+        let mut violations = Vec::new();
+
+        for pattern in FLAKY_PATTERNS.iter() {
+            for span in find_non_comment_matches(content, file_path, pattern.pattern) {
+                violations.push(Violation {
+                    rule: META.id.to_string(),
+                    severity: Severity::Low,
+                    failure: format!("{} can cause flaky tests. {}", pattern.name, pattern.reason),
+                    file: file_path.to_string(),
+                    line: Some(span.line),
+                    column: Some(span.column),
+                    end_line: Some(span.end_line),
+                    end_column: Some(span.end_column),
+                });
             }
+        }
 
-            violations
-        }),
+        violations
     }
 }
 
+pub fn rule() -> FlakyTestRule {
+    FlakyTestRule
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,7 +177,7 @@ mod tests {
     #[test]
     fn ignores_non_test_files() {
         let r = rule();
-        assert!(!r.file_pattern.is_match("/src/utils.ts"));
+        assert!(!r.applies_to("/src/utils.ts"));
     }
 
     #[test]
@@ -1,6 +1,7 @@
-use super::{Rule, Severity, Violation, RE_ALL_FILES};
+use super::{Category, Example, Language, Rule, RuleMeta, Severity, Violation};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::borrow::Cow;
 
 static GENERATED_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
     vec![
@@ -13,26 +14,74 @@ static GENERATED_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
     ]
 });
 
-pub fn rule() -> Rule {
-    Rule {
-        file_pattern: RE_ALL_FILES.clone(),
-        checker: Box::new(|_content: &str, file_path: &str| {
-            for pattern in GENERATED_PATTERNS.iter() {
-                if pattern.is_match(file_path) {
-                    return vec![Violation {
-                        rule: "generated-file".to_string(),
-                        severity: Severity::High,
-                        failure: "Do not edit generated files directly. Modify the source and regenerate.".to_string(),
-                        file: file_path.to_string(),
-                        line: None,
-                    }];
-                }
+pub static META: RuleMeta = RuleMeta {
+    id: Cow::Borrowed("generated-file"),
+    aliases: &["generatedFile"],
+    category: Category::Correctness,
+    severity: Severity::High,
+    languages: &[Language::Any],
+    checks_content: false,
+    description: Cow::Borrowed(
+        "Blocks edits to generated files (`*.generated.*`, `*.g.ts`, `generated/` directories); change the source they are generated from.",
+    ),
+    rationale: "Edits to generated files are lost the next time the generator runs.",
+    disable_when: "No code generation in project",
+    settings: &[],
+    examples: &[
+        Example {
+            path: "/src/api/client.generated.ts",
+            code: "",
+            flagged: true,
+        },
+        Example {
+            path: "/src/api/client.ts",
+            code: "",
+            flagged: false,
+        },
+        Example {
+            path: "/lib/models/user.g.dart",
+            code: "",
+            flagged: true,
+        },
+    ],
+};
+
+pub struct GeneratedFileRule;
+
+impl Rule for GeneratedFileRule {
+    fn meta(&self) -> &RuleMeta {
+        &META
+    }
+
+    fn applies_to(&self, _file_path: &str) -> bool {
+        true
+    }
+
+    fn check(&self, _content: &str, file_path: &str) -> Vec<Violation> {
+        for pattern in GENERATED_PATTERNS.iter() {
+            if pattern.is_match(file_path) {
+                return vec![Violation {
+                    rule: META.id.to_string(),
+                    severity: Severity::High,
+                    failure:
+                        "Do not edit generated files directly. Modify the source and regenerate."
+                            .to_string(),
+                    file: file_path.to_string(),
+                    line: None,
+                    column: None,
+                    end_line: None,
+                    end_column: None,
+                }];
             }
-            Vec::new()
-        }),
+        }
+        Vec::new()
     }
 }
 
+pub fn rule() -> GeneratedFileRule {
+    GeneratedFileRule
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
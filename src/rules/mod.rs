@@ -1,22 +1,33 @@
 mod architecture;
+mod bash;
 mod bundle_size;
+mod circular_import;
 mod crypto_weak;
+mod custom;
 mod dom_access;
 mod flaky_test;
 mod generated_file;
+mod hardcoded_secret;
 mod naming;
 mod security;
 mod sensitive_file;
 mod sensitive_logging;
+mod sensitive_read;
 mod sync_io;
 mod test_assertion;
 mod test_location;
 mod transaction;
 
-use crate::config::Config;
+use crate::config::{Config, RuleSetting, RulesConfig};
+use crate::lexer;
+
+pub use bash::{check_command, git_current_branch};
+pub use custom::CustomRegexRule;
 use once_cell::sync::Lazy;
 use regex::Regex;
-use serde::Deserialize;
+pub use sensitive_read::ReadPolicy;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 
 pub static RE_JS_FILE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"\.(tsx?|jsx?)$").expect("RE_JS_FILE: invalid regex"));
@@ -24,50 +35,107 @@ pub static RE_JS_FILE: Lazy<Regex> =
 pub static RE_TEST_FILE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"\.(test|spec)\.[jt]sx?$").expect("RE_TEST_FILE: invalid regex"));
 
-pub static RE_ALL_FILES: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r".").expect("RE_ALL_FILES: invalid regex"));
-
-/// Returns true if the line starts with a comment marker (does not detect inline comments).
-/// Note: For JSDoc-style block comments, only matches `* ` (with space) or bare `*` lines
-/// to avoid false positives on multiplication expressions like `x * y`.
-#[inline]
-fn starts_with_comment(line: &str) -> bool {
-    let trimmed = line.trim_start();
-    trimmed.starts_with("//")
-        || trimmed.starts_with("/*")
-        || trimmed.starts_with("* ")
-        || trimmed == "*"
-}
-
-/// Returns non-comment lines with 1-based line numbers.
-#[inline]
-pub(crate) fn non_comment_lines(content: &str) -> impl Iterator<Item = (u32, &str)> {
+/// Returns lines with comments blanked out and 1-based line numbers. Lines
+/// left empty, such as those inside a block comment, are skipped. Strings,
+/// regex literals and JSX text are kept as they are, so `"http://x"` or
+/// `/https:\/\//` is not mistaken for a comment. JSX is only lexed in
+/// `.jsx`/`.tsx` files, and files that aren't JS/TS are returned as is.
+pub(crate) fn non_comment_lines<'a>(
+    content: &'a str,
+    file_path: &str,
+) -> impl Iterator<Item = (u32, Cow<'a, str>)> {
+    let masked = if RE_JS_FILE.is_match(file_path) {
+        lexer::mask_comments(content, lexer::is_jsx_file(file_path))
+    } else {
+        content.to_string()
+    };
     content
         .lines()
+        .zip(masked.lines())
         .enumerate()
-        .filter(|(_, line)| !starts_with_comment(line))
-        .map(|(idx, line)| ((idx + 1) as u32, line))
+        .filter(|(_, (_, code))| !code.trim().is_empty())
+        .map(|(idx, (line, code))| {
+            let code = if code == line {
+                Cow::Borrowed(line)
+            } else {
+                Cow::Owned(code.to_string())
+            };
+            ((idx + 1) as u32, code)
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+}
+
+/// Where a match sits in a file. Lines and columns are 1-based, columns count
+/// characters, and `end_column` points just past the last matched character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub line: u32,
+    pub column: u32,
+    pub end_line: u32,
+    pub end_column: u32,
+}
+
+impl Span {
+    /// Span of the bytes `start..end` of `text`, which begins at `line`.
+    pub fn in_text(line: u32, text: &str, start: usize, end: usize) -> Self {
+        let position = |offset: usize| {
+            let before = &text[..offset];
+            match before.rfind('\n') {
+                Some(newline) => (
+                    before.matches('\n').count() as u32,
+                    before[newline + 1..].chars().count() as u32 + 1,
+                ),
+                None => (0, before.chars().count() as u32 + 1),
+            }
+        };
+        let (start_lines, column) = position(start);
+        let (end_lines, end_column) = position(end);
+        Self {
+            line: line + start_lines,
+            column,
+            end_line: line + end_lines,
+            end_column,
+        }
+    }
 }
 
-pub fn find_non_comment_match(content: &str, pattern: &Regex) -> Option<u32> {
-    non_comment_lines(content)
+/// Every match of `pattern` on non-comment lines, in file order.
+pub fn find_non_comment_matches<'a>(
+    content: &'a str,
+    file_path: &str,
+    pattern: &'a Regex,
+) -> impl Iterator<Item = Span> + 'a {
+    non_comment_lines(content, file_path).flat_map(move |(line_num, line)| {
+        pattern
+            .find_iter(&line)
+            .filter(|m| !m.is_empty())
+            .map(|m| Span::in_text(line_num, &line, m.start(), m.end()))
+            .collect::<Vec<_>>()
+    })
+}
+
+pub fn find_non_comment_match(content: &str, file_path: &str, pattern: &Regex) -> Option<u32> {
+    non_comment_lines(content, file_path)
         .find(|(_, line)| pattern.is_match(line))
         .map(|(line_num, _)| line_num)
 }
 
-pub fn count_non_comment_matches(content: &str, pattern: &Regex) -> usize {
-    non_comment_lines(content)
+pub fn count_non_comment_matches(content: &str, file_path: &str, pattern: &Regex) -> usize {
+    non_comment_lines(content, file_path)
         .filter(|(_, line)| pattern.is_match(line))
         .count()
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Severity {
     Critical,
     High,
     Medium,
     Low,
+    /// Informational only; never blocks or asks, whatever the severity policy says.
+    Info,
 }
 
 impl std::fmt::Display for Severity {
@@ -77,8 +145,9 @@ impl std::fmt::Display for Severity {
             Severity::High => "HIGH",
             Severity::Medium => "MEDIUM",
             Severity::Low => "LOW",
+            Severity::Info => "INFO",
         };
-        write!(f, "{}", s)
+        f.pad(s)
     }
 }
 
@@ -89,66 +158,473 @@ pub struct Violation {
     pub failure: String,
     pub file: String,
     pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub end_line: Option<u32>,
+    pub end_column: Option<u32>,
 }
 
-type Checker = Box<dyn Fn(&str, &str) -> Vec<Violation> + Send + Sync>;
-
-pub struct Rule {
-    pub file_pattern: Regex,
-    checker: Checker,
+/// What a rule guards against, for grouping in `guardrails rules list`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Category {
+    Security,
+    Architecture,
+    Correctness,
+    Performance,
+    Testing,
+    Style,
+    Lint,
 }
 
-impl Rule {
-    pub fn check(&self, content: &str, file_path: &str) -> Vec<Violation> {
-        (self.checker)(content, file_path)
+impl std::fmt::Display for Category {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Category::Security => "security",
+            Category::Architecture => "architecture",
+            Category::Correctness => "correctness",
+            Category::Performance => "performance",
+            Category::Testing => "testing",
+            Category::Style => "style",
+            Category::Lint => "lint",
+        };
+        f.pad(s)
     }
 }
 
-pub fn load_rules(config: &Config) -> Vec<Rule> {
-    let mut rules = Vec::new();
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    JavaScript,
+    TypeScript,
+    Python,
+    Html,
+    Shell,
+    /// Any file, e.g. rules that only look at the path.
+    Any,
+}
 
-    if config.rules.sensitive_file {
-        rules.push(sensitive_file::rule());
-    }
-    if config.rules.architecture {
-        rules.push(architecture::rule());
+impl std::fmt::Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Language::JavaScript => "javascript",
+            Language::TypeScript => "typescript",
+            Language::Python => "python",
+            Language::Html => "html",
+            Language::Shell => "shell",
+            Language::Any => "any",
+        };
+        f.pad(s)
     }
-    if config.rules.naming {
-        rules.push(naming::rule());
+}
+
+pub(crate) const JS_TS: &[Language] = &[Language::JavaScript, Language::TypeScript];
+
+/// Code the rule flags (or allows), checked against the rule by the tests below.
+/// A leading `// path: code` line stands for another file the example needs.
+#[derive(Debug, Serialize)]
+pub struct Example {
+    pub path: &'static str,
+    pub code: &'static str,
+    pub flagged: bool,
+}
+
+/// Describes a rule. Built-in rules keep theirs in a static; custom rules build one from config.
+#[derive(Debug, Serialize)]
+pub struct RuleMeta {
+    /// Rule id used in violations, e.g. `sync-io`.
+    pub id: Cow<'static, str>,
+    /// Other names for the rule: its config key and any extra violation ids.
+    pub aliases: &'static [&'static str],
+    pub category: Category,
+    /// Severity reported unless config overrides it.
+    pub severity: Severity,
+    pub languages: &'static [Language],
+    /// Whether findings point at lines of the file's content. Only those can
+    /// be covered by a `guardrails-ignore` directive; path and command rules
+    /// set this to false.
+    pub checks_content: bool,
+    pub description: Cow<'static, str>,
+    /// Why the rule exists, for `guardrails explain`.
+    pub rationale: &'static str,
+    /// When a project is better off turning the rule off.
+    pub disable_when: &'static str,
+    /// Config settings specific to this rule, besides `rules.<key>`.
+    pub settings: &'static [Setting],
+    pub examples: &'static [Example],
+}
+
+/// A config setting that tunes one rule.
+#[derive(Debug, Serialize)]
+pub struct Setting {
+    pub key: &'static str,
+    pub description: &'static str,
+}
+
+pub trait Rule: Send + Sync {
+    fn meta(&self) -> &RuleMeta;
+
+    /// Whether the rule looks at `file_path` at all.
+    fn applies_to(&self, file_path: &str) -> bool;
+
+    fn check(&self, content: &str, file_path: &str) -> Vec<Violation>;
+}
+
+/// Reports with the severity configured for the rule.
+struct Configured {
+    rule: Box<dyn Rule>,
+    setting: RuleSetting,
+}
+
+impl Rule for Configured {
+    fn meta(&self) -> &RuleMeta {
+        self.rule.meta()
     }
-    if config.rules.transaction {
-        rules.push(transaction::rule());
+
+    fn applies_to(&self, file_path: &str) -> bool {
+        self.rule.applies_to(file_path)
     }
-    if config.rules.security {
-        rules.push(security::rule());
+
+    fn check(&self, content: &str, file_path: &str) -> Vec<Violation> {
+        let mut violations = self.rule.check(content, file_path);
+        self.setting.apply(&mut violations);
+        violations
     }
-    if config.rules.crypto_weak {
-        rules.push(crypto_weak::rule());
+}
+
+fn configured(rule: impl Rule + 'static, setting: &RuleSetting) -> Box<dyn Rule> {
+    if setting.severity.is_none() {
+        return Box::new(rule);
     }
-    if config.rules.generated_file {
-        rules.push(generated_file::rule());
+    Box::new(Configured {
+        rule: Box::new(rule),
+        setting: setting.clone(),
+    })
+}
+
+type Build = fn(&Config) -> Box<dyn Rule>;
+
+/// A built-in rule: its metadata, config key and constructor.
+pub struct Registration {
+    pub meta: &'static RuleMeta,
+    /// Key under `rules` in config, e.g. `syncIo`.
+    pub key: &'static str,
+    pub setting: fn(&RulesConfig) -> &RuleSetting,
+    /// None for checks that run outside the file rules (biome, Bash, Read).
+    build: Option<Build>,
+}
+
+impl Registration {
+    /// Whether this rule reports violations with `rule_id`.
+    pub fn covers(&self, rule_id: &str) -> bool {
+        if self.key == "biome" {
+            return rule_id.starts_with("biome/");
+        }
+        self.meta.id == rule_id || self.meta.aliases.contains(&rule_id)
     }
-    if config.rules.test_location {
-        rules.push(test_location::rule());
+
+    /// Whether `name` (config key, rule id or alias) refers to this rule.
+    fn is_named(&self, name: &str) -> bool {
+        self.key == name || self.meta.id == name || self.meta.aliases.contains(&name)
     }
-    if config.rules.dom_access {
-        rules.push(dom_access::rule());
+}
+
+/// Every built-in rule, in the order they run.
+pub static REGISTRY: &[Registration] = &[
+    Registration {
+        meta: &sensitive_file::META,
+        key: "sensitiveFile",
+        setting: |r| &r.sensitive_file,
+        build: Some(|c| configured(sensitive_file::rule(), &c.rules.sensitive_file)),
+    },
+    Registration {
+        meta: &architecture::META,
+        key: "architecture",
+        setting: |r| &r.architecture,
+        build: Some(|c| configured(architecture::rule(&c.architecture), &c.rules.architecture)),
+    },
+    Registration {
+        meta: &naming::META,
+        key: "naming",
+        setting: |r| &r.naming,
+        build: Some(|c| configured(naming::rule(), &c.rules.naming)),
+    },
+    Registration {
+        meta: &transaction::META,
+        key: "transaction",
+        setting: |r| &r.transaction,
+        build: Some(|c| configured(transaction::rule(), &c.rules.transaction)),
+    },
+    Registration {
+        meta: &security::META,
+        key: "security",
+        setting: |r| &r.security,
+        build: Some(|c| Box::new(security::rule(&c.rules.security))),
+    },
+    Registration {
+        meta: &crypto_weak::META,
+        key: "cryptoWeak",
+        setting: |r| &r.crypto_weak,
+        build: Some(|c| Box::new(crypto_weak::rule(&c.rules.crypto_weak))),
+    },
+    Registration {
+        meta: &generated_file::META,
+        key: "generatedFile",
+        setting: |r| &r.generated_file,
+        build: Some(|c| configured(generated_file::rule(), &c.rules.generated_file)),
+    },
+    Registration {
+        meta: &test_location::META,
+        key: "testLocation",
+        setting: |r| &r.test_location,
+        build: Some(|c| configured(test_location::rule(), &c.rules.test_location)),
+    },
+    Registration {
+        meta: &dom_access::META,
+        key: "domAccess",
+        setting: |r| &r.dom_access,
+        build: Some(|c| configured(dom_access::rule(), &c.rules.dom_access)),
+    },
+    Registration {
+        meta: &sync_io::META,
+        key: "syncIo",
+        setting: |r| &r.sync_io,
+        build: Some(|c| configured(sync_io::rule(), &c.rules.sync_io)),
+    },
+    Registration {
+        meta: &bundle_size::META,
+        key: "bundleSize",
+        setting: |r| &r.bundle_size,
+        build: Some(|c| configured(bundle_size::rule(), &c.rules.bundle_size)),
+    },
+    Registration {
+        meta: &circular_import::META,
+        key: "circularImport",
+        setting: |r| &r.circular_import,
+        build: Some(|c| configured(circular_import::rule(), &c.rules.circular_import)),
+    },
+    Registration {
+        meta: &test_assertion::META,
+        key: "testAssertion",
+        setting: |r| &r.test_assertion,
+        build: Some(|c| configured(test_assertion::rule(), &c.rules.test_assertion)),
+    },
+    Registration {
+        meta: &flaky_test::META,
+        key: "flakyTest",
+        setting: |r| &r.flaky_test,
+        build: Some(|c| configured(flaky_test::rule(), &c.rules.flaky_test)),
+    },
+    Registration {
+        meta: &sensitive_logging::META,
+        key: "sensitiveLogging",
+        setting: |r| &r.sensitive_logging,
+        build: Some(|c| configured(sensitive_logging::rule(), &c.rules.sensitive_logging)),
+    },
+    Registration {
+        meta: &hardcoded_secret::META,
+        key: "hardcodedSecret",
+        setting: |r| &r.hardcoded_secret,
+        build: Some(|c| configured(hardcoded_secret::rule(), &c.rules.hardcoded_secret)),
+    },
+    Registration {
+        meta: &BIOME,
+        key: "biome",
+        setting: |r| &r.biome,
+        build: None,
+    },
+    Registration {
+        meta: &bash::META,
+        key: "bash",
+        setting: |r| &r.bash,
+        build: None,
+    },
+    Registration {
+        meta: &sensitive_read::META,
+        key: "sensitiveRead",
+        setting: |r| &r.sensitive_read,
+        build: None,
+    },
+];
+
+static BIOME: RuleMeta = RuleMeta {
+    id: Cow::Borrowed("biome"),
+    aliases: &[],
+    category: Category::Lint,
+    severity: Severity::High,
+    languages: JS_TS,
+    checks_content: true,
+    description: Cow::Borrowed(
+        "Runs `biome lint` on the new content and reports its diagnostics as `biome/<category>`.",
+    ),
+    rationale: "Biome's linter catches common bugs and style problems the built-in rules don't cover, using the project's `biome.json`.",
+    disable_when: "Projects linted by another tool, or where biome is not installed",
+    settings: &[],
+    examples: &[],
+};
+
+/// The registered rule for a config key, rule id or alias.
+pub fn lookup(name: &str) -> Option<&'static Registration> {
+    REGISTRY.iter().find(|r| r.is_named(name))
+}
+
+/// Whether `name` (a config key like `syncIo` or a rule id like `sync-io`) covers `rule_id`.
+pub fn rule_matches(name: &str, rule_id: &str) -> bool {
+    name == rule_id || lookup(name).is_some_and(|r| r.covers(rule_id))
+}
+
+/// Whether `name` is a known config key or rule id.
+pub fn is_known_rule(name: &str) -> bool {
+    name.starts_with("biome/") || name == crate::config::GUARDRAILS_CONFIG || lookup(name).is_some()
+}
+
+/// Who reports `rule_id`: `biome`, `guardrails`, or `custom` for `customRules`.
+pub fn source(rule_id: &str) -> &'static str {
+    if rule_id.starts_with("biome/") {
+        "biome"
+    } else if rule_id == crate::suppress::INVALID_SUPPRESSION
+        || rule_id == crate::config::GUARDRAILS_CONFIG
+        || REGISTRY.iter().any(|r| r.covers(rule_id))
+    {
+        "guardrails"
+    } else {
+        "custom"
     }
-    if config.rules.sync_io {
-        rules.push(sync_io::rule());
+}
+
+pub fn load_rules(config: &Config) -> Vec<Box<dyn Rule>> {
+    let mut rules: Vec<Box<dyn Rule>> = REGISTRY
+        .iter()
+        .filter(|r| (r.setting)(&config.rules).enabled)
+        .filter_map(|r| r.build.map(|build| build(config)))
+        .collect();
+    rules.extend(
+        custom_rules(config)
+            .into_iter()
+            .map(|r| Box::new(r) as Box<dyn Rule>),
+    );
+    rules
+}
+
+/// The `customRules` entries that compile.
+pub fn custom_rules(config: &Config) -> Vec<CustomRegexRule> {
+    config
+        .custom_rules
+        .iter()
+        .filter_map(custom::rule)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spans_count_characters() {
+        let re = Regex::new(r"fs\.\w+Sync").unwrap();
+        let content = "// fs.readFileSync\nconst é = fs.readFileSync(a) + fs.existsSync(b);\n";
+        let spans: Vec<Span> = find_non_comment_matches(content, "/src/a.ts", &re).collect();
+        assert_eq!(
+            spans,
+            [
+                Span {
+                    line: 2,
+                    column: 11,
+                    end_line: 2,
+                    end_column: 26,
+                },
+                Span {
+                    line: 2,
+                    column: 32,
+                    end_line: 2,
+                    end_column: 45,
+                },
+            ]
+        );
+        assert_eq!(
+            Span::in_text(4, "a\nbc\ndef", 3, 8),
+            Span {
+                line: 5,
+                column: 2,
+                end_line: 6,
+                end_column: 4,
+            }
+        );
     }
-    if config.rules.bundle_size {
-        rules.push(bundle_size::rule());
+
+    #[test]
+    fn comments_are_masked_by_token() {
+        let re = Regex::new(r"innerHTML").unwrap();
+        let content = "/*\n * el.innerHTML = a;\n */ el.innerHTML = b; // innerHTML\nconst re = /https:\\/\\//; el.innerHTML = c;\n";
+        let lines: Vec<u32> = find_non_comment_matches(content, "/src/a.ts", &re)
+            .map(|s| s.line)
+            .collect();
+        assert_eq!(lines, [3, 4]);
+        assert_eq!(count_non_comment_matches(content, "/src/a.ts", &re), 2);
+        assert_eq!(find_non_comment_match(content, "/src/a.ts", &re), Some(3));
     }
-    if config.rules.test_assertion {
-        rules.push(test_assertion::rule());
+
+    #[test]
+    fn registry_names_are_unique() {
+        for r in REGISTRY {
+            let names = [r.key, &*r.meta.id]
+                .into_iter()
+                .chain(r.meta.aliases.iter().copied());
+            for name in names {
+                assert_eq!(lookup(name).map(|l| l.key), Some(r.key), "{}", name);
+            }
+        }
     }
-    if config.rules.flaky_test {
-        rules.push(flaky_test::rule());
+
+    #[test]
+    fn examples_match_rules() {
+        let config = Config::default();
+        for r in REGISTRY {
+            let Some(build) = r.build else {
+                continue;
+            };
+            let rule = build(&config);
+            for example in r.meta.examples {
+                // Examples that need another file show it in a leading
+                // `// path: code` comment; their rule's tests set it up.
+                let first = example.code.lines().next().unwrap_or_default();
+                if first.starts_with("// ") && first.contains(": ") {
+                    continue;
+                }
+                let found = rule.applies_to(example.path)
+                    && rule
+                        .check(example.code, example.path)
+                        .iter()
+                        .any(|v| r.covers(&v.rule));
+                assert_eq!(found, example.flagged, "{}: {:?}", r.key, example.code);
+            }
+        }
     }
-    if config.rules.sensitive_logging {
-        rules.push(sensitive_logging::rule());
+
+    #[test]
+    fn hook_examples_match_checks() {
+        let bash = crate::config::BashConfig::default();
+        for example in bash::META.examples {
+            let found = !check_command(example.code, &bash, &|| None).is_empty();
+            assert_eq!(found, example.flagged, "{}", example.code);
+        }
+        let read = ReadPolicy::new(&crate::config::ReadConfig::default());
+        for example in sensitive_read::META.examples {
+            let found = !read.check("Read", &[example.path]).is_empty();
+            assert_eq!(found, example.flagged, "{}", example.path);
+        }
     }
 
-    rules
+    #[test]
+    fn names_and_sources() {
+        assert!(rule_matches("syncIo", "sync-io"));
+        assert!(rule_matches("sync-io", "sync-io"));
+        assert!(rule_matches("bash", "force-push"));
+        assert!(rule_matches("biome", "biome/lint/style/useConst"));
+        assert!(!rule_matches("naming", "sync-io"));
+        assert!(is_known_rule("naming-convention"));
+        assert!(!is_known_rule("no-axios"));
+        assert_eq!(source("sync-io"), "guardrails");
+        assert_eq!(source("biome/lint/style/useConst"), "biome");
+        assert_eq!(source("no-axios"), "custom");
+    }
 }
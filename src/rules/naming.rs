@@ -1,6 +1,10 @@
-use super::{find_non_comment_match, Rule, Severity, Violation, RE_JS_FILE};
+use super::{
+    find_non_comment_match, find_non_comment_matches, Category, Example, Rule, RuleMeta, Severity,
+    Violation, JS_TS, RE_JS_FILE,
+};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::borrow::Cow;
 
 struct NamingIssue {
     pattern: &'static Lazy<Regex>,
@@ -69,39 +73,90 @@ static NAMING_ISSUES: Lazy<[NamingIssue; 4]> = Lazy::new(|| {
     ]
 });
 
-pub fn rule() -> Rule {
-    Rule {
-        file_pattern: RE_JS_FILE.clone(),
-        checker: Box::new(|content: &str, file_path: &str| {
-            let mut violations = Vec::new();
-
-            for issue in NAMING_ISSUES.iter() {
-                if let Some(fp) = issue.file_pattern {
-                    if !fp.is_match(file_path) {
-                        continue;
-                    }
-                }
-                if let Some(ac) = issue.additional_check {
-                    if find_non_comment_match(content, ac).is_none() {
-                        continue;
-                    }
+pub static META: RuleMeta = RuleMeta {
+    id: Cow::Borrowed("naming-convention"),
+    aliases: &["naming"],
+    category: Category::Style,
+    severity: Severity::Medium,
+    languages: JS_TS,
+    checks_content: true,
+    description: Cow::Borrowed(
+        "Enforces naming conventions: PascalCase components, `use` prefix for hooks in `hooks/`, PascalCase interfaces and types.",
+    ),
+    rationale: "Consistent names let readers and tools tell components, hooks and types apart; React only applies its hook rules to functions named `use...`.",
+    disable_when: "Different naming conventions in team/project",
+    settings: &[],
+    examples: &[
+        Example {
+            path: "/src/types.ts",
+            code: "interface user { name: string; }",
+            flagged: true,
+        },
+        Example {
+            path: "/src/types.ts",
+            code: "interface User { name: string; }",
+            flagged: false,
+        },
+        Example {
+            path: "/src/hooks/useFetch.ts",
+            code: "const fetchData = () => { const [data] = useState(null); return data; };",
+            flagged: true,
+        },
+        Example {
+            path: "/src/hooks/useFetch.ts",
+            code: "const useFetch = () => { const [d] = useState(); return d; };",
+            flagged: false,
+        },
+    ],
+};
+
+pub struct NamingRule;
+
+impl Rule for NamingRule {
+    fn meta(&self) -> &RuleMeta {
+        &META
+    }
+
+    fn applies_to(&self, file_path: &str) -> bool {
+        RE_JS_FILE.is_match(file_path)
+    }
+
+    fn check(&self, content: &str, file_path: &str) -> Vec<Violation> {
+        let mut violations = Vec::new();
+
+        for issue in NAMING_ISSUES.iter() {
+            if let Some(fp) = issue.file_pattern {
+                if !fp.is_match(file_path) {
+                    continue;
                 }
-                if let Some(line_num) = find_non_comment_match(content, issue.pattern) {
-                    violations.push(Violation {
-                        rule: "naming-convention".to_string(),
-                        severity: issue.severity,
-                        failure: issue.failure.to_string(),
-                        file: file_path.to_string(),
-                        line: Some(line_num),
-                    });
+            }
+            if let Some(ac) = issue.additional_check {
+                if find_non_comment_match(content, file_path, ac).is_none() {
+                    continue;
                 }
             }
+            for span in find_non_comment_matches(content, file_path, issue.pattern) {
+                violations.push(Violation {
+                    rule: META.id.to_string(),
+                    severity: issue.severity,
+                    failure: issue.failure.to_string(),
+                    file: file_path.to_string(),
+                    line: Some(span.line),
+                    column: Some(span.column),
+                    end_line: Some(span.end_line),
+                    end_column: Some(span.end_column),
+                });
+            }
+        }
 
-            violations
-        }),
+        violations
     }
 }
 
+pub fn rule() -> NamingRule {
+    NamingRule
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,6 +1,11 @@
-use super::{find_non_comment_match, Rule, Severity, Violation, RE_JS_FILE};
+use super::{
+    find_non_comment_matches, Category, Example, Language, Rule, RuleMeta, Setting, Severity,
+    Violation, RE_JS_FILE,
+};
+use crate::config::RuleSetting;
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::borrow::Cow;
 
 static RE_HTML_FILE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"\.(jsx?|tsx?|html?)$").expect("RE_HTML_FILE: invalid regex"));
@@ -31,6 +36,8 @@ static RE_SESSION_STORAGE_SENSITIVE: Lazy<Regex> = Lazy::new(|| {
 });
 
 struct SecurityIssue {
+    /// Key for per-pattern settings under `rules.security.patterns`.
+    id: &'static str,
     pattern: &'static Lazy<Regex>,
     file_pattern: &'static Lazy<Regex>,
     failure: &'static str,
@@ -39,48 +46,56 @@ struct SecurityIssue {
 
 static SECURITY_ISSUES: [SecurityIssue; 8] = [
     SecurityIssue {
+        id: "documentWrite",
         pattern: &RE_DOC_WRITE,
         file_pattern: &RE_HTML_FILE,
         failure: "Use createElement/appendChild instead",
         severity: Severity::High,
     },
     SecurityIssue {
+        id: "innerHTML",
         pattern: &RE_INNER_HTML,
         file_pattern: &RE_HTML_FILE,
         failure: "Use textContent or DOMPurify.sanitize() instead",
         severity: Severity::High,
     },
     SecurityIssue {
+        id: "setTimeoutString",
         pattern: &RE_SET_TIMEOUT_STR,
         file_pattern: &RE_JS_FILE,
         failure: "Use function reference: setTimeout(() => { ... }, delay)",
         severity: Severity::High,
     },
     SecurityIssue {
+        id: "setIntervalString",
         pattern: &RE_SET_INTERVAL_STR,
         file_pattern: &RE_JS_FILE,
         failure: "Use function reference: setInterval(() => { ... }, delay)",
         severity: Severity::High,
     },
     SecurityIssue {
+        id: "postMessageWildcard",
         pattern: &RE_POST_MESSAGE_STAR,
         file_pattern: &RE_JS_FILE,
         failure: "Specify exact target origin instead of '*'",
         severity: Severity::High,
     },
     SecurityIssue {
+        id: "outerHTML",
         pattern: &RE_OUTER_HTML,
         file_pattern: &RE_HTML_FILE,
         failure: "Use DOM methods instead",
         severity: Severity::Medium,
     },
     SecurityIssue {
+        id: "localStorageSecret",
         pattern: &RE_LOCAL_STORAGE_SENSITIVE,
         file_pattern: &RE_JS_FILE,
         failure: "Use httpOnly cookies for sensitive data",
         severity: Severity::Medium,
     },
     SecurityIssue {
+        id: "sessionStorageSecret",
         pattern: &RE_SESSION_STORAGE_SENSITIVE,
         file_pattern: &RE_JS_FILE,
         failure: "Use httpOnly cookies for sensitive data",
@@ -88,29 +103,95 @@ static SECURITY_ISSUES: [SecurityIssue; 8] = [
     },
 ];
 
-pub fn rule() -> Rule {
-    Rule {
-        file_pattern: RE_HTML_FILE.clone(),
-        checker: Box::new(|content: &str, file_path: &str| {
-            let mut violations = Vec::new();
-
-            for issue in SECURITY_ISSUES.iter() {
-                if !issue.file_pattern.is_match(file_path) {
-                    continue;
-                }
-                if let Some(line_num) = find_non_comment_match(content, issue.pattern) {
-                    violations.push(Violation {
-                        rule: "security".to_string(),
-                        severity: issue.severity,
-                        failure: issue.failure.to_string(),
-                        file: file_path.to_string(),
-                        line: Some(line_num),
-                    });
-                }
+pub static META: RuleMeta = RuleMeta {
+    id: Cow::Borrowed("security"),
+    aliases: &[],
+    category: Category::Security,
+    severity: Severity::High,
+    languages: &[Language::JavaScript, Language::TypeScript, Language::Html],
+    checks_content: true,
+    description: Cow::Borrowed(
+        "Flags XSS and injection sinks (`innerHTML`, `document.write`, string `setTimeout`), `postMessage` to `*`, and secrets in web storage.",
+    ),
+    rationale: "These APIs run or render strings that may come from users, the usual route to XSS and code injection. Web storage is readable by any script on the page.",
+    disable_when: "Never (security critical)",
+    settings: &[
+        Setting {
+            key: "rules.security.patterns.<id>",
+            description: "Turn off or re-rate one pattern: documentWrite, innerHTML, outerHTML, setTimeoutString, setIntervalString, postMessageWildcard, localStorageSecret, sessionStorageSecret",
+        },
+    ],
+    examples: &[
+        Example {
+            path: "/src/utils/render.ts",
+            code: "element.innerHTML = userInput;",
+            flagged: true,
+        },
+        Example {
+            path: "/src/utils/render.ts",
+            code: "element.textContent = userInput;",
+            flagged: false,
+        },
+        Example {
+            path: "/src/utils/messaging.ts",
+            code: r#"window.parent.postMessage(data, '*');"#,
+            flagged: true,
+        },
+        Example {
+            path: "/src/utils/messaging.ts",
+            code: r#"window.parent.postMessage(data, 'https://example.com');"#,
+            flagged: false,
+        },
+    ],
+};
+
+pub struct SecurityRule {
+    setting: RuleSetting,
+}
+
+impl Rule for SecurityRule {
+    fn meta(&self) -> &RuleMeta {
+        &META
+    }
+
+    fn applies_to(&self, file_path: &str) -> bool {
+        RE_HTML_FILE.is_match(file_path)
+    }
+
+    /// Applies the per-pattern settings itself, so load_rules doesn't wrap it.
+    fn check(&self, content: &str, file_path: &str) -> Vec<Violation> {
+        let mut violations = Vec::new();
+
+        for issue in SECURITY_ISSUES.iter() {
+            if !issue.file_pattern.is_match(file_path) {
+                continue;
             }
+            let Some(severity) = self.setting.pattern_severity(issue.id, issue.severity) else {
+                continue;
+            };
+            for span in find_non_comment_matches(content, file_path, issue.pattern) {
+                violations.push(Violation {
+                    rule: META.id.to_string(),
+                    severity,
+                    failure: issue.failure.to_string(),
+                    file: file_path.to_string(),
+                    line: Some(span.line),
+                    column: Some(span.column),
+                    end_line: Some(span.end_line),
+                    end_column: Some(span.end_column),
+                });
+            }
+        }
 
-            violations
-        }),
+        violations
+    }
+}
+
+pub fn rule(setting: &RuleSetting) -> SecurityRule {
+    let ids: Vec<&str> = SECURITY_ISSUES.iter().map(|i| i.id).collect();
+    setting.warn_unknown_patterns("security", &ids);
+    SecurityRule {
+        setting: setting.clone(),
     }
 }
 
@@ -119,7 +200,7 @@ mod tests {
     use super::*;
 
     fn check(content: &str, path: &str) -> Vec<Violation> {
-        rule().check(content, path)
+        rule(&RuleSetting::default()).check(content, path)
     }
 
     #[test]
@@ -130,6 +211,23 @@ mod tests {
         assert!(!check(outer, "/src/component.tsx").is_empty());
     }
 
+    #[test]
+    fn reports_every_occurrence() {
+        let content = "a.innerHTML = x;\n// b.innerHTML = y;\nc.innerHTML = y; d.innerHTML = z;\n";
+        let spans: Vec<_> = check(content, "/src/component.tsx")
+            .iter()
+            .map(|v| (v.line, v.column, v.end_column))
+            .collect();
+        assert_eq!(
+            spans,
+            [
+                (Some(1), Some(2), Some(14)),
+                (Some(3), Some(2), Some(14)),
+                (Some(3), Some(19), Some(31)),
+            ]
+        );
+    }
+
     #[test]
     fn detects_code_injection() {
         let cases = ["setTimeout('alert(1)', 100);", "setInterval('fn()', 1000);"];
@@ -166,4 +264,16 @@ mod tests {
             assert!(check(content, path).is_empty());
         }
     }
+
+    #[test]
+    fn pattern_settings_override_severity() {
+        let setting: RuleSetting = serde_json::from_str(
+            r#"{"severity": "medium", "patterns": {"innerHTML": "low", "postMessageWildcard": "off"}}"#,
+        )
+        .unwrap();
+        let check = |content| rule(&setting).check(content, "/src/a.ts");
+        assert_eq!(check("el.innerHTML = x;")[0].severity, Severity::Low);
+        assert_eq!(check("document.write(x);")[0].severity, Severity::Medium);
+        assert!(check("window.postMessage(data, '*');").is_empty());
+    }
 }
@@ -1,37 +1,94 @@
-use super::{Rule, Severity, Violation, RE_ALL_FILES};
+use super::{Category, Example, Language, Rule, RuleMeta, Severity, Violation};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::borrow::Cow;
+
+/// Path patterns for secrets. Also the built-in deny list for reads.
+pub(crate) const SENSITIVE_PATTERN_SOURCES: [&str; 9] = [
+    r"\.env(\.[a-zA-Z]+)?$",
+    r"credentials\.[a-zA-Z]+$",
+    r"_credentials\.[a-zA-Z]+$",
+    r"_key\.[a-zA-Z]+$",
+    r"_secret\.[a-zA-Z]+$",
+    r"\.pem$",
+    r"\.key$",
+    r"id_rsa",
+    r"id_ed25519",
+];
 
 static SENSITIVE_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
-    vec![
-        Regex::new(r"\.env(\.[a-zA-Z]+)?$").expect("env pattern"),
-        Regex::new(r"credentials\.[a-zA-Z]+$").expect("credentials pattern"),
-        Regex::new(r"_credentials\.[a-zA-Z]+$").expect("_credentials pattern"),
-        Regex::new(r"_key\.[a-zA-Z]+$").expect("_key pattern"),
-        Regex::new(r"_secret\.[a-zA-Z]+$").expect("_secret pattern"),
-        Regex::new(r"\.pem$").expect("pem pattern"),
-        Regex::new(r"\.key$").expect("key pattern"),
-        Regex::new(r"id_rsa").expect("id_rsa pattern"),
-        Regex::new(r"id_ed25519").expect("id_ed25519 pattern"),
-    ]
+    SENSITIVE_PATTERN_SOURCES
+        .iter()
+        .map(|p| Regex::new(p).expect("SENSITIVE_PATTERNS: invalid regex"))
+        .collect()
 });
 
-pub fn rule() -> Rule {
-    Rule {
-        file_pattern: RE_ALL_FILES.clone(),
-        checker: Box::new(|_content: &str, file_path: &str| {
-            if SENSITIVE_PATTERNS.iter().any(|p| p.is_match(file_path)) {
-                return vec![Violation {
-                    rule: "sensitive-file".to_string(),
-                    severity: Severity::Critical,
-                    failure: "Do not write to sensitive files. Use environment variables or secret management.".to_string(),
-                    file: file_path.to_string(),
-                    line: None,
-                }];
-            }
-            Vec::new()
-        }),
+pub(crate) fn is_sensitive_path(path: &str) -> bool {
+    SENSITIVE_PATTERNS.iter().any(|p| p.is_match(path))
+}
+
+pub static META: RuleMeta = RuleMeta {
+    id: Cow::Borrowed("sensitive-file"),
+    aliases: &["sensitiveFile"],
+    category: Category::Security,
+    severity: Severity::Critical,
+    languages: &[Language::Any],
+    checks_content: false,
+    description: Cow::Borrowed(
+        "Blocks writes to secret files such as `.env`, `*.pem`, `*_credentials.*` and SSH keys.",
+    ),
+    rationale: "Secrets written by an agent end up in commits, logs and transcripts. Keeping them out of the tree is the cheapest way to avoid leaking them.",
+    disable_when: "Never (security critical)",
+    settings: &[],
+    examples: &[
+        Example {
+            path: "/project/.env.local",
+            code: "",
+            flagged: true,
+        },
+        Example {
+            path: "/project/src/env.ts",
+            code: "",
+            flagged: false,
+        },
+        Example {
+            path: "/home/user/.ssh/id_rsa",
+            code: "",
+            flagged: true,
+        },
+    ],
+};
+
+pub struct SensitiveFileRule;
+
+impl Rule for SensitiveFileRule {
+    fn meta(&self) -> &RuleMeta {
+        &META
     }
+
+    fn applies_to(&self, _file_path: &str) -> bool {
+        true
+    }
+
+    fn check(&self, _content: &str, file_path: &str) -> Vec<Violation> {
+        if is_sensitive_path(file_path) {
+            return vec![Violation {
+                rule: META.id.to_string(),
+                severity: Severity::Critical,
+                failure: "Do not write to sensitive files. Use environment variables or secret management.".to_string(),
+                file: file_path.to_string(),
+                line: None,
+                column: None,
+                end_line: None,
+                end_column: None,
+            }];
+        }
+        Vec::new()
+    }
+}
+
+pub fn rule() -> SensitiveFileRule {
+    SensitiveFileRule
 }
 
 #[cfg(test)]
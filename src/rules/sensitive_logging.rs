@@ -1,7 +1,11 @@
-use super::{Rule, Severity, Violation, RE_JS_FILE};
-use crate::scanner::{build_line_offsets, offset_to_line, StringScanner};
+use super::{Category, Example, Language, Rule, RuleMeta, Severity, Span, Violation, RE_JS_FILE};
+use crate::lexer::{
+    build_line_offsets, find_closing, is_jsx_file, offset_to_line, token_at, tokenize, Token,
+    TokenKind,
+};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::borrow::Cow;
 
 static RE_CONSOLE_CALL: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"console\.(log|warn|error|info|debug)\s*\(")
@@ -18,139 +22,247 @@ static RE_SENSITIVE_KEYWORD: Lazy<Regex> = Lazy::new(|| {
         .expect("RE_SENSITIVE_KEYWORD: invalid regex")
 });
 
-fn extract_paren_content(content: &str, start: usize) -> Option<&str> {
-    let bytes = content.as_bytes();
-    let mut scanner = StringScanner::new(bytes, start);
-    let mut depth = 1;
+static RE_PY_FILE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\.py$").expect("RE_PY_FILE: invalid regex"));
 
-    while scanner.pos < bytes.len() && depth > 0 {
-        let in_context = scanner.skip_for_bracket_matching();
-        let byte = scanner.current();
+static RE_PY_LOG_CALL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"\b(print|(logging|logger|log)\.(debug|info|warning|warn|error|critical|exception))\s*\(",
+    )
+    .expect("RE_PY_LOG_CALL: invalid regex")
+});
+
+/// Whether an identifier in `tokens[start..end]` is a sensitive keyword.
+/// Strings, comments and template text don't count; interpolations do.
+fn contains_sensitive_keyword(content: &str, tokens: &[Token], start: usize, end: usize) -> bool {
+    tokens
+        .iter()
+        .skip_while(|t| t.start < start)
+        .take_while(|t| t.end <= end)
+        .any(|t| t.kind == TokenKind::Ident && RE_SENSITIVE_KEYWORD.is_match(t.text(content)))
+}
+
+/// Code portions of a Python line: string literals are blanked except f-string
+/// `{...}` fields, and a trailing `#` comment is removed. Each character stays
+/// in its column, so spans found in the result point into the line.
+/// Note: Triple-quoted strings spanning lines are not tracked.
+fn python_code_portions(line: &str) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut code = String::new();
+    let mut i = 0;
 
-        scanner.advance();
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '#' {
+            break;
+        }
+        if c != '\'' && c != '"' {
+            code.push(c);
+            i += 1;
+            continue;
+        }
 
-        if !in_context {
-            match byte {
-                Some(b'(') => depth += 1,
-                Some(b')') => depth -= 1,
-                _ => {}
+        let is_fstring = i > 0 && matches!(chars[i - 1], 'f' | 'F');
+        let mut depth = 0;
+        code.push(' ');
+        i += 1;
+        while i < chars.len() && !(chars[i] == c && depth == 0) {
+            match chars[i] {
+                '\\' if depth == 0 => {
+                    i += 1;
+                    code.push_str(if i < chars.len() { "  " } else { " " });
+                }
+                '{' if is_fstring => {
+                    depth += 1;
+                    code.push(' ');
+                }
+                '}' if is_fstring && depth > 0 => {
+                    depth -= 1;
+                    code.push(' ');
+                }
+                ch if depth > 0 => code.push(ch),
+                _ => code.push(' '),
             }
+            i += 1;
         }
+        if i < chars.len() {
+            code.push(' ');
+        }
+        i += 1;
     }
 
-    if depth == 0 {
-        Some(&content[start..scanner.pos - 1])
-    } else {
-        None
-    }
+    code
 }
 
-fn is_in_comment(content: &str, pos: usize) -> bool {
-    let line_start = content[..pos].rfind('\n').map(|i| i + 1).unwrap_or(0);
-    let bytes = content.as_bytes();
-    let mut scanner = StringScanner::new(bytes, line_start);
-
-    while scanner.pos < pos {
-        scanner.advance();
+/// Byte offset just past the `)` closing the call whose arguments start at
+/// `from`, or the end of the code when the call continues on later lines.
+fn python_call_end(code: &str, from: usize) -> usize {
+    let mut depth = 0;
+    for (i, b) in code.bytes().enumerate().skip(from) {
+        match b {
+            b'(' => depth += 1,
+            b')' if depth == 0 => return i + 1,
+            b')' => depth -= 1,
+            _ => {}
+        }
     }
-
-    scanner.in_block_comment || scanner.in_line_comment
+    code.trim_end().len()
 }
 
-/// Extract code portions (excluding strings and comments) for keyword matching.
-/// Template interpolations (${...}) are included as code.
-fn extract_code_portions(content: &str) -> String {
-    let bytes = content.as_bytes();
-    let mut scanner = StringScanner::new(bytes, 0);
-    let mut code = String::new();
-
-    while scanner.pos < bytes.len() {
-        let byte = scanner.current();
-
-        let in_interpolation = !scanner.template_interp_depth.is_empty()
-            && !scanner.in_single_quote
-            && !scanner.in_double_quote;
-
-        let skip = (scanner.in_single_quote
-            || scanner.in_double_quote
-            || scanner.in_template
-            || scanner.in_block_comment
-            || scanner.in_line_comment)
-            && !in_interpolation;
-
-        scanner.advance();
+/// Python variant for `print()` and `logging`/`logger` calls, used for `.py`
+/// files and Python notebook cells.
+fn check_python(content: &str, file_path: &str) -> Vec<Violation> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let code = python_code_portions(line);
+            let call = RE_PY_LOG_CALL.find(&code)?;
+            if !RE_SENSITIVE_KEYWORD.is_match(&code[call.end()..]) {
+                return None;
+            }
+            let end = python_call_end(&code, call.end());
+            Some(Span::in_text((idx + 1) as u32, &code, call.start(), end))
+        })
+        .map(|span| Violation {
+            rule: META.id.to_string(),
+            severity: Severity::High,
+            failure:
+                "Logging sensitive data (password, token, secret). Remove or mask before logging."
+                    .to_string(),
+            file: file_path.to_string(),
+            line: Some(span.line),
+            column: Some(span.column),
+            end_line: Some(span.end_line),
+            end_column: Some(span.end_column),
+        })
+        .collect()
+}
 
-        if !skip {
-            if let Some(b) = byte {
-                if b.is_ascii() {
-                    code.push(b as char);
+fn check_js(content: &str, file_path: &str) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let mut reported_lines = std::collections::HashSet::new();
+    let line_offsets = build_line_offsets(content);
+    let tokens = tokenize(content, is_jsx_file(file_path));
+
+    let check_match = |caps: regex::Match,
+                       violations: &mut Vec<Violation>,
+                       reported_lines: &mut std::collections::HashSet<usize>,
+                       msg: &str| {
+        // Skip calls inside comments, strings, regexes and JSX text.
+        if token_at(&tokens, caps.start()).map(|t| t.kind) != Some(TokenKind::Ident) {
+            return;
+        }
+        if let Some(close) = find_closing(&tokens, content, caps.end(), b'(', b')') {
+            if contains_sensitive_keyword(content, &tokens, caps.end(), close) {
+                let line_num = offset_to_line(&line_offsets, caps.start());
+                if reported_lines.insert(line_num) {
+                    let line_start = content[..caps.start()].rfind('\n').map_or(0, |i| i + 1);
+                    // Through the closing parenthesis.
+                    let end = close + 1;
+                    let span = Span::in_text(
+                        line_num as u32,
+                        &content[line_start..],
+                        caps.start() - line_start,
+                        end - line_start,
+                    );
+                    violations.push(Violation {
+                        rule: META.id.to_string(),
+                        severity: Severity::High,
+                        failure: msg.to_string(),
+                        file: file_path.to_string(),
+                        line: Some(span.line),
+                        column: Some(span.column),
+                        end_line: Some(span.end_line),
+                        end_column: Some(span.end_column),
+                    });
                 }
             }
         }
+    };
+
+    for caps in RE_CONSOLE_CALL.find_iter(content) {
+        check_match(
+            caps,
+            &mut violations,
+            &mut reported_lines,
+            "Logging sensitive data (password, token, secret). Remove or mask before logging.",
+        );
     }
 
-    code
-}
+    for caps in RE_LOGGER_CALL.find_iter(content) {
+        check_match(
+            caps,
+            &mut violations,
+            &mut reported_lines,
+            "Logging sensitive data via logger. Remove or mask before logging.",
+        );
+    }
 
-fn contains_sensitive_keyword(content: &str) -> bool {
-    let code = extract_code_portions(content);
-    RE_SENSITIVE_KEYWORD.is_match(&code)
+    violations
 }
 
-pub fn rule() -> Rule {
-    Rule {
-        file_pattern: RE_JS_FILE.clone(),
-        checker: Box::new(|content: &str, file_path: &str| {
-            let mut violations = Vec::new();
-            let mut reported_lines = std::collections::HashSet::new();
-            let line_offsets = build_line_offsets(content);
-
-            let check_match = |caps: regex::Match,
-                               violations: &mut Vec<Violation>,
-                               reported_lines: &mut std::collections::HashSet<usize>,
-                               msg: &str| {
-                if is_in_comment(content, caps.start()) {
-                    return;
-                }
-                if let Some(args) = extract_paren_content(content, caps.end()) {
-                    if contains_sensitive_keyword(args) {
-                        let line_num = offset_to_line(&line_offsets, caps.start());
-                        if reported_lines.insert(line_num) {
-                            violations.push(Violation {
-                                rule: "sensitive-logging".to_string(),
-                                severity: Severity::High,
-                                failure: msg.to_string(),
-                                file: file_path.to_string(),
-                                line: Some(line_num as u32),
-                            });
-                        }
-                    }
-                }
-            };
-
-            for caps in RE_CONSOLE_CALL.find_iter(content) {
-                check_match(
-                    caps,
-                    &mut violations,
-                    &mut reported_lines,
-                    "Logging sensitive data (password, token, secret). Remove or mask before logging.",
-                );
-            }
+pub static META: RuleMeta = RuleMeta {
+    id: Cow::Borrowed("sensitive-logging"),
+    aliases: &["sensitiveLogging"],
+    category: Category::Security,
+    severity: Severity::High,
+    languages: &[Language::JavaScript, Language::TypeScript, Language::Python],
+    checks_content: true,
+    description: Cow::Borrowed(
+        "Flags console, logger and Python logging calls whose arguments mention passwords, tokens, secrets or credentials.",
+    ),
+    rationale: "Logs are copied to files, terminals and monitoring services. A secret written there is hard to remove from every copy.",
+    disable_when: "Never (security critical)",
+    settings: &[],
+    examples: &[
+        Example {
+            path: "/src/auth/login.ts",
+            code: "console.log('User password:', password);",
+            flagged: true,
+        },
+        Example {
+            path: "/src/auth/login.ts",
+            code: "console.log('User logged in:', userId);",
+            flagged: false,
+        },
+        Example {
+            path: "/src/auth.py",
+            code: r#"logging.info("token=%s", token)"#,
+            flagged: true,
+        },
+        Example {
+            path: "/src/auth.py",
+            code: r#"print('password reset requested')"#,
+            flagged: false,
+        },
+    ],
+};
+
+pub struct SensitiveLoggingRule;
+
+impl Rule for SensitiveLoggingRule {
+    fn meta(&self) -> &RuleMeta {
+        &META
+    }
 
-            for caps in RE_LOGGER_CALL.find_iter(content) {
-                check_match(
-                    caps,
-                    &mut violations,
-                    &mut reported_lines,
-                    "Logging sensitive data via logger. Remove or mask before logging.",
-                );
-            }
+    fn applies_to(&self, file_path: &str) -> bool {
+        RE_JS_FILE.is_match(file_path) || RE_PY_FILE.is_match(file_path)
+    }
 
-            violations
-        }),
+    fn check(&self, content: &str, file_path: &str) -> Vec<Violation> {
+        if RE_PY_FILE.is_match(file_path) {
+            check_python(content, file_path)
+        } else {
+            check_js(content, file_path)
+        }
     }
 }
 
+pub fn rule() -> SensitiveLoggingRule {
+    SensitiveLoggingRule
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -244,6 +356,54 @@ mod tests {
         assert!(check(content).is_empty());
     }
 
+    fn check_py(content: &str) -> Vec<Violation> {
+        rule().check(content, "/src/auth.py")
+    }
+
+    #[test]
+    fn python_detects_sensitive_logging() {
+        let cases = [
+            "print('password:', password)",
+            "logging.info(\"token=%s\", token)",
+            "logger.debug(f\"secret is {secret}\")",
+            "    print(user.password)",
+        ];
+        for content in cases {
+            assert_eq!(check_py(content).len(), 1, "Should detect: {}", content);
+        }
+    }
+
+    #[test]
+    fn python_findings_span_the_call() {
+        let v = &check_py("x = 'é'; print(\"pw\", password)  # done")[0];
+        assert_eq!((v.column, v.end_column), (Some(10), Some(31)));
+        let v = &check_py("logger.info('%s', token,\n    other)")[0];
+        assert_eq!((v.line, v.end_line), (Some(1), Some(1)));
+        assert_eq!((v.column, v.end_column), (Some(1), Some(25)));
+    }
+
+    #[test]
+    fn python_allows_safe_logging() {
+        let cases = [
+            "print('password reset requested')",
+            "logging.info(f\"user {user_id} logged in\")",
+            "# print(password)",
+            "print(user_id)  # not the password",
+        ];
+        for content in cases {
+            assert!(check_py(content).is_empty(), "Should allow: {}", content);
+        }
+    }
+
+    #[test]
+    fn regex_and_jsx_text_are_not_comments() {
+        let content = r"const re = /https:\/\//; console.log(re, token);";
+        assert_eq!(check(content).len(), 1);
+
+        let content = "const a = <p>Don't log console.log(password)</p>;";
+        assert!(rule().check(content, "/src/auth/login.tsx").is_empty());
+    }
+
     #[test]
     fn ignores_inline_block_comment() {
         let content = "console.log(/* password */ 'masked');";
@@ -0,0 +1,179 @@
+use super::{
+    sensitive_file::SENSITIVE_PATTERN_SOURCES, Category, Example, Language, RuleMeta, Setting,
+    Severity, Violation,
+};
+use crate::config::ReadConfig;
+use regex::Regex;
+use std::borrow::Cow;
+
+/// Read/Grep/Glob paths are checked by the hook directly, not as file rules.
+pub static META: RuleMeta = RuleMeta {
+    id: Cow::Borrowed("sensitive-read"),
+    aliases: &["sensitiveRead"],
+    category: Category::Security,
+    severity: Severity::Critical,
+    languages: &[Language::Any],
+    checks_content: false,
+    description: Cow::Borrowed(
+        "Blocks Read, Grep and Glob access to secret files such as `.env` and private keys; `.env.example` stays readable.",
+    ),
+    rationale: "Reading a secret file puts its contents into the model's context and the session transcript.",
+    disable_when: "Never (security critical)",
+    settings: &[
+        Setting {
+            key: "read.deny",
+            description: "Extra path regexes to block",
+        },
+        Setting {
+            key: "read.allow",
+            description: "Path regexes that stay readable (default: .env.example, .env.sample, .env.template)",
+        },
+        Setting {
+            key: "read.severity",
+            description: "Severity of blocked reads (default: critical)",
+        },
+    ],
+    examples: &[
+        Example {
+            path: "/project/.env",
+            code: "",
+            flagged: true,
+        },
+        Example {
+            path: "/project/.env.example",
+            code: "",
+            flagged: false,
+        },
+    ],
+};
+
+fn compile(patterns: impl IntoIterator<Item = impl AsRef<str>>) -> Vec<Regex> {
+    patterns
+        .into_iter()
+        .filter_map(|p| match Regex::new(p.as_ref()) {
+            Ok(re) => Some(re),
+            Err(e) => {
+                eprintln!(
+                    "guardrails: warning: invalid read pattern {:?}: {}",
+                    p.as_ref(),
+                    e
+                );
+                None
+            }
+        })
+        .collect()
+}
+
+/// Strip trailing wildcards so `**/.env*` is checked as `**/.env`.
+fn trim_glob(pattern: &str) -> &str {
+    pattern.trim_end_matches(['*', '?'])
+}
+
+/// Deny list for Read/Grep/Glob: the sensitive file patterns plus configured extras.
+pub struct ReadPolicy {
+    deny: Vec<Regex>,
+    allow: Vec<Regex>,
+    config: ReadConfig,
+}
+
+impl ReadPolicy {
+    pub fn new(config: &ReadConfig) -> Self {
+        Self {
+            deny: compile(
+                SENSITIVE_PATTERN_SOURCES
+                    .iter()
+                    .copied()
+                    .chain(config.deny.iter().map(String::as_str)),
+            ),
+            allow: compile(&config.allow),
+            config: config.clone(),
+        }
+    }
+
+    fn is_denied(&self, path: &str) -> bool {
+        if self.allow.iter().any(|re| re.is_match(path)) {
+            return false;
+        }
+        self.deny.iter().any(|re| re.is_match(path))
+    }
+
+    /// Check the paths or glob patterns a read-only tool would access.
+    pub fn check(&self, tool_name: &str, paths: &[&str]) -> Vec<Violation> {
+        paths
+            .iter()
+            .filter(|p| !p.is_empty())
+            .filter(|p| self.is_denied(p) || self.is_denied(trim_glob(p)))
+            .map(|p| Violation {
+                rule: META.id.to_string(),
+                severity: self.config.severity,
+                failure: format!(
+                    "{} would expose secrets to the model. Ask the user for the specific non-secret values you need.",
+                    tool_name
+                ),
+                file: p.to_string(),
+                line: None,
+                column: None,
+                end_line: None,
+                end_column: None,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(tool: &str, paths: &[&str]) -> Vec<Violation> {
+        ReadPolicy::new(&ReadConfig::default()).check(tool, paths)
+    }
+
+    #[test]
+    fn detects_secret_reads() {
+        let cases = [
+            "/project/.env",
+            "/project/.env.local",
+            "/home/u/.ssh/id_rsa",
+            "/project/certs/server.pem",
+            "/project/aws_credentials.json",
+        ];
+        for path in cases {
+            assert_eq!(check("Read", &[path]).len(), 1, "Should deny: {}", path);
+        }
+    }
+
+    #[test]
+    fn detects_glob_patterns() {
+        assert_eq!(check("Glob", &["**/.env*"]).len(), 1);
+        assert_eq!(check("Glob", &["**/*.pem"]).len(), 1);
+        assert_eq!(check("Grep", &["/project", "*.key"]).len(), 1);
+    }
+
+    #[test]
+    fn allows_templates() {
+        assert!(check("Read", &["/project/.env.example"]).is_empty());
+        assert!(check("Read", &["/project/.env.sample"]).is_empty());
+        assert!(check("Read", &["/project/.env.template"]).is_empty());
+    }
+
+    #[test]
+    fn allows_normal_files() {
+        assert!(check("Read", &["/project/src/index.ts"]).is_empty());
+        assert!(check("Glob", &["**/*.ts", ""]).is_empty());
+    }
+
+    #[test]
+    fn uses_configured_lists_and_severity() {
+        let config: ReadConfig = serde_json::from_str(
+            r#"{"severity": "medium", "deny": ["\\.npmrc$"], "allow": ["fixtures/"]}"#,
+        )
+        .unwrap();
+        let policy = ReadPolicy::new(&config);
+        let violations = policy.check("Read", &["/home/u/.npmrc"]);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].severity, crate::rules::Severity::Medium);
+        assert!(policy
+            .check("Read", &["/project/fixtures/test.pem"])
+            .is_empty());
+    }
+}
@@ -1,6 +1,10 @@
-use super::{find_non_comment_match, Rule, Severity, Violation, RE_JS_FILE};
+use super::{
+    find_non_comment_matches, Category, Example, Rule, RuleMeta, Severity, Violation, JS_TS,
+    RE_JS_FILE,
+};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::borrow::Cow;
 
 static RE_EXCLUDED_FILE: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"(\.config\.[jt]s$|/scripts?/|/cli/|/bin/|\.mjs$)")
@@ -66,37 +70,83 @@ static SYNC_IO: Lazy<[SyncIo; 6]> = Lazy::new(|| {
     ]
 });
 
-pub fn rule() -> Rule {
-    Rule {
-        file_pattern: RE_JS_FILE.clone(),
-        checker: Box::new(|content: &str, file_path: &str| {
-            // Allow sync I/O in config files and CLI scripts
-            if RE_EXCLUDED_FILE.is_match(file_path) {
-                return Vec::new();
-            }
+pub static META: RuleMeta = RuleMeta {
+    id: Cow::Borrowed("sync-io"),
+    aliases: &["syncIo"],
+    category: Category::Performance,
+    severity: Severity::Medium,
+    languages: JS_TS,
+    checks_content: true,
+    description: Cow::Borrowed(
+        "Flags synchronous `fs` calls (`readFileSync`, `writeFileSync`, `existsSync`, ...) outside config files and CLI scripts.",
+    ),
+    rationale: "Synchronous file system calls block the event loop, so a server stops answering every other request while one waits on disk.",
+    disable_when: "CLI tools, build scripts, or sync-only contexts",
+    settings: &[],
+    examples: &[
+        Example {
+            path: "/src/utils/file.ts",
+            code: r#"const data = fs.readFileSync('file.txt', 'utf8');"#,
+            flagged: true,
+        },
+        Example {
+            path: "/scripts/build.ts",
+            code: r#"const data = fs.readFileSync('file.txt', 'utf8');"#,
+            flagged: false,
+        },
+        Example {
+            path: "/src/utils/file.ts",
+            code: "if (fs.existsSync(path)) { }",
+            flagged: true,
+        },
+    ],
+};
+
+pub struct SyncIoRule;
 
-            let mut violations = Vec::new();
-
-            for io in SYNC_IO.iter() {
-                if let Some(line_num) = find_non_comment_match(content, io.pattern) {
-                    violations.push(Violation {
-                        rule: "sync-io".to_string(),
-                        severity: Severity::Medium,
-                        failure: format!(
-                            "{} blocks the event loop. Use {} instead.",
-                            io.method, io.async_alternative
-                        ),
-                        file: file_path.to_string(),
-                        line: Some(line_num),
-                    });
-                }
+impl Rule for SyncIoRule {
+    fn meta(&self) -> &RuleMeta {
+        &META
+    }
+
+    fn applies_to(&self, file_path: &str) -> bool {
+        RE_JS_FILE.is_match(file_path)
+    }
+
+    fn check(&self, content: &str, file_path: &str) -> Vec<Violation> {
+        // Allow sync I/O in config files and CLI scripts
+        if RE_EXCLUDED_FILE.is_match(file_path) {
+            return Vec::new();
+        }
+
+        let mut violations = Vec::new();
+
+        for io in SYNC_IO.iter() {
+            for span in find_non_comment_matches(content, file_path, io.pattern) {
+                violations.push(Violation {
+                    rule: META.id.to_string(),
+                    severity: Severity::Medium,
+                    failure: format!(
+                        "{} blocks the event loop. Use {} instead.",
+                        io.method, io.async_alternative
+                    ),
+                    file: file_path.to_string(),
+                    line: Some(span.line),
+                    column: Some(span.column),
+                    end_line: Some(span.end_line),
+                    end_column: Some(span.end_column),
+                });
             }
+        }
 
-            violations
-        }),
+        violations
     }
 }
 
+pub fn rule() -> SyncIoRule {
+    SyncIoRule
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,7 +1,11 @@
-use super::{Rule, Severity, Violation, RE_TEST_FILE};
-use crate::scanner::{build_line_offsets, offset_to_line, StringScanner};
+use super::{Category, Example, Rule, RuleMeta, Severity, Span, Violation, JS_TS, RE_TEST_FILE};
+use crate::lexer::{
+    build_line_offsets, find_closing, is_jsx_file, mask_comments, offset_to_line, token_at,
+    tokenize, TokenKind,
+};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::borrow::Cow;
 
 static RE_TEST_START: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r#"(it|test)\s*\(\s*['"]([^'"]+)['"]\s*,\s*(async\s*)?\(\s*\)\s*=>\s*\{"#)
@@ -13,74 +17,110 @@ static RE_ASSERTION: Lazy<Regex> = Lazy::new(|| {
         .expect("RE_ASSERTION: invalid regex")
 });
 
-fn extract_brace_content(content: &str, start: usize) -> Option<&str> {
-    let bytes = content.as_bytes();
-    let mut scanner = StringScanner::new(bytes, start);
-    let mut depth = 1;
-
-    while scanner.pos < bytes.len() && depth > 0 {
-        let byte = scanner.current();
-        let in_context = scanner.skip_for_bracket_matching();
-        scanner.advance();
-
-        if !in_context {
-            match byte {
-                Some(b'{') => depth += 1,
-                Some(b'}') => depth -= 1,
-                _ => {}
-            }
-        }
+pub static META: RuleMeta = RuleMeta {
+    id: Cow::Borrowed("test-assertion"),
+    aliases: &["testAssertion"],
+    category: Category::Testing,
+    severity: Severity::Medium,
+    languages: JS_TS,
+    checks_content: true,
+    description: Cow::Borrowed(
+        "Flags `it`/`test` blocks that contain code but no `expect` or `assert` call.",
+    ),
+    rationale: "A test without assertions passes whatever the code does, so it reports coverage that isn't there.",
+    disable_when: "Playwright, custom test frameworks",
+    settings: &[],
+    examples: &[
+        Example {
+            path: "/src/utils.test.ts",
+            code: r#"it('should do something', () => {
+  const result = doSomething();
+});"#,
+            flagged: true,
+        },
+        Example {
+            path: "/src/utils.test.ts",
+            code: r#"it('should return true', () => {
+  expect(doSomething()).toBe(true);
+});"#,
+            flagged: false,
+        },
+        Example {
+            path: "/src/utils.test.ts",
+            code: r#"it('todo', () => {
+  // TODO
+});"#,
+            flagged: false,
+        },
+    ],
+};
+
+pub struct TestAssertionRule;
+
+impl Rule for TestAssertionRule {
+    fn meta(&self) -> &RuleMeta {
+        &META
     }
 
-    if depth == 0 {
-        Some(&content[start..scanner.pos - 1])
-    } else {
-        None
+    fn applies_to(&self, file_path: &str) -> bool {
+        RE_TEST_FILE.is_match(file_path)
     }
-}
-
-pub fn rule() -> Rule {
-    Rule {
-        file_pattern: RE_TEST_FILE.clone(),
-        checker: Box::new(|content: &str, file_path: &str| {
-            let mut violations = Vec::new();
-            let line_offsets = build_line_offsets(content);
 
-            for caps in RE_TEST_START.captures_iter(content) {
-                let test_name = caps.get(2).map(|m| m.as_str()).unwrap_or("unknown");
-                let match_end = caps.get(0).map(|m| m.end()).unwrap_or(0);
+    fn check(&self, content: &str, file_path: &str) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        let line_offsets = build_line_offsets(content);
+        let tokens = tokenize(content, is_jsx_file(file_path));
 
-                let test_body = extract_brace_content(content, match_end).unwrap_or("");
+        for caps in RE_TEST_START.captures_iter(content) {
+            let test_name = caps.get(2).map(|m| m.as_str()).unwrap_or("unknown");
+            let test_start = caps.get(0).map(|m| m.start()).unwrap_or(0);
+            let match_end = caps.get(0).map(|m| m.end()).unwrap_or(0);
 
-                if RE_ASSERTION.is_match(test_body) {
-                    continue;
-                }
+            // Skip `it(` inside comments, strings and regexes.
+            if token_at(&tokens, test_start).map(|t| t.kind) != Some(TokenKind::Ident) {
+                continue;
+            }
 
-                let trimmed = test_body.trim();
-                if trimmed.is_empty() || trimmed.starts_with("//") {
-                    continue;
-                }
+            let test_body = find_closing(&tokens, content, match_end, b'{', b'}')
+                .map_or("", |end| &content[match_end..end]);
+            let code = mask_comments(test_body, is_jsx_file(file_path));
 
-                let test_start = caps.get(0).map(|m| m.start()).unwrap_or(0);
-                let line_num = offset_to_line(&line_offsets, test_start);
-
-                violations.push(Violation {
-                    rule: "test-assertion".to_string(),
-                    severity: Severity::Medium,
-                    failure: format!(
-                        "Test '{}' has no assertions. Add expect() or assert calls.",
-                        test_name
-                    ),
-                    file: file_path.to_string(),
-                    line: Some(line_num as u32),
-                });
+            if RE_ASSERTION.is_match(&code) || code.trim().is_empty() {
+                continue;
             }
 
-            violations
-        }),
+            let line_num = offset_to_line(&line_offsets, test_start) as u32;
+            let line_start = content[..test_start].rfind('\n').map_or(0, |i| i + 1);
+            let span = Span::in_text(
+                line_num,
+                &content[line_start..],
+                test_start - line_start,
+                match_end - line_start,
+            );
+
+            violations.push(Violation {
+                rule: META.id.to_string(),
+                severity: Severity::Medium,
+                failure: format!(
+                    "Test '{}' has no assertions. Add expect() or assert calls.",
+                    test_name
+                ),
+                file: file_path.to_string(),
+                line: Some(span.line),
+                column: Some(span.column),
+                end_line: Some(span.end_line),
+                end_column: Some(span.end_column),
+            });
+        }
+
+        violations
     }
 }
 
+pub fn rule() -> TestAssertionRule {
+    TestAssertionRule
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -263,6 +303,31 @@ mod tests {
         assert_eq!(violations.len(), 1);
     }
 
+    #[test]
+    fn code_after_leading_comment_is_checked() {
+        let content = r#"
+            it('should fail without assertion', () => {
+                // setup
+                doSomething();
+            });
+        "#;
+        assert_eq!(check(content).len(), 1);
+    }
+
+    #[test]
+    fn ignores_commented_out_assertion_and_test() {
+        let content = r#"
+            /* it('old', () => { doSomething(); }); */
+            it('should fail without assertion', () => {
+                doSomething(); // expect(x).toBe(1);
+                const re = /}/;
+            });
+        "#;
+        let violations = check(content);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].line, Some(3));
+    }
+
     #[test]
     fn handles_string_inside_interpolation() {
         let content = r#"
@@ -1,6 +1,7 @@
-use super::{Rule, Severity, Violation, RE_ALL_FILES};
+use super::{Category, Example, Language, Rule, RuleMeta, Severity, Violation};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::borrow::Cow;
 
 static RE_SRC_DIR: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"/src/").expect("RE_SRC_DIR: invalid regex"));
@@ -13,32 +14,77 @@ static TEST_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
     ]
 });
 
-pub fn rule() -> Rule {
-    Rule {
-        file_pattern: RE_ALL_FILES.clone(),
-        checker: Box::new(|_content: &str, file_path: &str| {
-            if !RE_SRC_DIR.is_match(file_path) {
-                return Vec::new();
-            }
+pub static META: RuleMeta = RuleMeta {
+    id: Cow::Borrowed("test-location"),
+    aliases: &["testLocation"],
+    category: Category::Testing,
+    severity: Severity::Medium,
+    languages: &[Language::Any],
+    checks_content: false,
+    description: Cow::Borrowed(
+        "Flags test files placed under `src/`; tests belong in `tests/` or `__tests__/` outside the source tree.",
+    ),
+    rationale: "Keeping tests out of `src/` keeps them out of builds and puts the test tree in one predictable place.",
+    disable_when: "Co-located test strategy (tests next to source)",
+    settings: &[],
+    examples: &[
+        Example {
+            path: "/project/src/utils/helper.test.ts",
+            code: "",
+            flagged: true,
+        },
+        Example {
+            path: "/project/tests/helper.test.ts",
+            code: "",
+            flagged: false,
+        },
+        Example {
+            path: "/project/src/__tests__/utils.ts",
+            code: "",
+            flagged: true,
+        },
+    ],
+};
+
+pub struct TestLocationRule;
 
-            for pattern in TEST_PATTERNS.iter() {
-                if pattern.is_match(file_path) {
-                    return vec![Violation {
-                        rule: "test-location".to_string(),
-                        severity: Severity::Medium,
-                        failure:
-                            "Test files should be in tests/ or __tests__/ directory outside src/"
-                                .to_string(),
-                        file: file_path.to_string(),
-                        line: None,
-                    }];
-                }
+impl Rule for TestLocationRule {
+    fn meta(&self) -> &RuleMeta {
+        &META
+    }
+
+    fn applies_to(&self, _file_path: &str) -> bool {
+        true
+    }
+
+    fn check(&self, _content: &str, file_path: &str) -> Vec<Violation> {
+        if !RE_SRC_DIR.is_match(file_path) {
+            return Vec::new();
+        }
+
+        for pattern in TEST_PATTERNS.iter() {
+            if pattern.is_match(file_path) {
+                return vec![Violation {
+                    rule: META.id.to_string(),
+                    severity: Severity::Medium,
+                    failure: "Test files should be in tests/ or __tests__/ directory outside src/"
+                        .to_string(),
+                    file: file_path.to_string(),
+                    line: None,
+                    column: None,
+                    end_line: None,
+                    end_column: None,
+                }];
             }
-            Vec::new()
-        }),
+        }
+        Vec::new()
     }
 }
 
+pub fn rule() -> TestLocationRule {
+    TestLocationRule
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
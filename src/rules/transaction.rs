@@ -1,8 +1,10 @@
 use super::{
-    count_non_comment_matches, find_non_comment_match, Rule, Severity, Violation, RE_JS_FILE,
+    count_non_comment_matches, find_non_comment_match, find_non_comment_matches, Category, Example,
+    Rule, RuleMeta, Severity, Violation, JS_TS, RE_JS_FILE,
 };
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::borrow::Cow;
 
 static RE_TARGET_DIR: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"/(usecases?|use-cases?|application|services?|domain|handlers?|app)/")
@@ -21,35 +23,82 @@ static RE_TX_BOUNDARY: Lazy<Regex> = Lazy::new(|| {
     .expect("RE_TX_BOUNDARY: invalid regex")
 });
 
-pub fn rule() -> Rule {
-    Rule {
-        file_pattern: RE_JS_FILE.clone(),
-        checker: Box::new(|content: &str, file_path: &str| {
-            if !RE_TARGET_DIR.is_match(file_path) {
-                return Vec::new();
-            }
+pub static META: RuleMeta = RuleMeta {
+    id: Cow::Borrowed("transaction-boundary"),
+    aliases: &["transaction"],
+    category: Category::Correctness,
+    severity: Severity::Medium,
+    languages: JS_TS,
+    checks_content: true,
+    description: Cow::Borrowed(
+        "Flags use case, service and domain files with two or more writes (`save`, `create`, `update`, ...) and no transaction boundary.",
+    ),
+    rationale: "Several writes without a transaction can leave data half-updated when one of them fails.",
+    disable_when: "Non-database projects",
+    settings: &[],
+    examples: &[
+        Example {
+            path: "/src/usecases/handler.ts",
+            code: "async function handle() {\n  await user.save();\n  await order.create();\n}\n",
+            flagged: true,
+        },
+        Example {
+            path: "/src/usecases/handler.ts",
+            code: "await db.transaction(async (tx) => { await tx.insert(users); await tx.insert(orders); });",
+            flagged: false,
+        },
+        Example {
+            path: "/src/utils/helper.ts",
+            code: "async function handle() {\n  await user.save();\n  await order.create();\n}\n",
+            flagged: false,
+        },
+    ],
+};
 
-            let write_count = count_non_comment_matches(content, &RE_WRITE_OPS);
-            if write_count < 2 {
-                return Vec::new();
-            }
+pub struct TransactionRule;
 
-            if find_non_comment_match(content, &RE_TX_BOUNDARY).is_some() {
-                return Vec::new();
-            }
+impl Rule for TransactionRule {
+    fn meta(&self) -> &RuleMeta {
+        &META
+    }
 
-            vec![Violation {
-                rule: "transaction-boundary".to_string(),
-                severity: Severity::Medium,
-                failure: format!(
-                    "Add transaction boundary (UnitOfWork, @Transactional, or explicit tx) - {} write ops detected",
-                    write_count
-                ),
-                file: file_path.to_string(),
-                line: find_non_comment_match(content, &RE_WRITE_OPS),
-            }]
-        }),
+    fn applies_to(&self, file_path: &str) -> bool {
+        RE_JS_FILE.is_match(file_path)
     }
+
+    fn check(&self, content: &str, file_path: &str) -> Vec<Violation> {
+        if !RE_TARGET_DIR.is_match(file_path) {
+            return Vec::new();
+        }
+
+        let write_count = count_non_comment_matches(content, file_path, &RE_WRITE_OPS);
+        if write_count < 2 {
+            return Vec::new();
+        }
+
+        if find_non_comment_match(content, file_path, &RE_TX_BOUNDARY).is_some() {
+            return Vec::new();
+        }
+
+        let first_write = find_non_comment_matches(content, file_path, &RE_WRITE_OPS).next();
+        vec![Violation {
+            rule: META.id.to_string(),
+            severity: Severity::Medium,
+            failure: format!(
+                "Add transaction boundary (UnitOfWork, @Transactional, or explicit tx) - {} write ops detected",
+                write_count
+            ),
+            file: file_path.to_string(),
+            line: first_write.map(|s| s.line),
+            column: first_write.map(|s| s.column),
+            end_line: first_write.map(|s| s.end_line),
+            end_column: first_write.map(|s| s.end_column),
+        }]
+    }
+}
+
+pub fn rule() -> TransactionRule {
+    TransactionRule
 }
 
 #[cfg(test)]
@@ -0,0 +1,369 @@
+//! Runs biome and the custom rules against a single file.
+
+use crate::baseline::BaselineCache;
+use crate::biome;
+use crate::config::{Config, Decision, RuleSetting};
+use crate::fingerprint;
+use crate::rules::{self, Rule, Violation};
+use crate::suppress::{self, Suppression, Trust};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+fn is_js_ts_file(path: &str) -> bool {
+    path.ends_with(".ts")
+        || path.ends_with(".tsx")
+        || path.ends_with(".js")
+        || path.ends_with(".jsx")
+}
+
+/// Findings for one file, with those hidden by inline directives listed separately.
+#[derive(Debug, Default)]
+pub struct Checked {
+    pub violations: Vec<Violation>,
+    pub suppressed: Vec<Suppression>,
+}
+
+impl From<Vec<Violation>> for Checked {
+    fn from(violations: Vec<Violation>) -> Self {
+        Self {
+            violations,
+            suppressed: Vec::new(),
+        }
+    }
+}
+
+impl Checked {
+    pub fn extend(&mut self, other: Checked) {
+        self.violations.extend(other.violations);
+        self.suppressed.extend(other.suppressed);
+    }
+
+    /// Order by file, line and column for stable output.
+    pub fn sort(&mut self) {
+        fn key(v: &Violation) -> (&str, Option<u32>, Option<u32>) {
+            (&v.file, v.line, v.column)
+        }
+        self.violations.sort_by(|a, b| key(a).cmp(&key(b)));
+        self.suppressed
+            .sort_by(|a, b| key(&a.violation).cmp(&key(&b.violation)));
+    }
+}
+
+/// Rules for one set of matching overrides.
+struct RuleSet {
+    rules: Vec<Box<dyn Rule>>,
+    biome: bool,
+    biome_severity: RuleSetting,
+}
+
+impl RuleSet {
+    fn new(config: &Config) -> Self {
+        Self {
+            rules: rules::load_rules(config),
+            biome: config.rules.biome.enabled,
+            biome_severity: config.rules.biome.clone(),
+        }
+    }
+}
+
+/// Rules and biome availability, resolved once so a file can be checked repeatedly.
+pub struct Runner {
+    config: Config,
+    base: Arc<RuleSet>,
+    /// Rule sets keyed by the indices of the overrides that matched a file.
+    scoped: Mutex<HashMap<Vec<usize>, Arc<RuleSet>>>,
+    biome: bool,
+    biome_server: bool,
+    /// `customRules` ids, accepted by suppression directives.
+    custom_ids: Vec<String>,
+    /// None when findings are being recorded into a baseline.
+    baseline: Option<BaselineCache>,
+}
+
+impl Runner {
+    pub fn new(config: &Config) -> Self {
+        let uses_biome = config.rules.biome.enabled
+            || config
+                .overrides
+                .iter()
+                .any(|o| o.rules.contains_key("biome"));
+        let biome = uses_biome && biome::is_available();
+        if uses_biome && !biome {
+            eprintln!("guardrails: biome not found in PATH, skipping biome checks");
+        }
+        Self {
+            config: config.clone(),
+            base: Arc::new(RuleSet::new(config)),
+            scoped: Mutex::new(HashMap::new()),
+            biome,
+            biome_server: false,
+            custom_ids: config.custom_rules.iter().map(|c| c.id.clone()).collect(),
+            baseline: Some(BaselineCache::default()),
+        }
+    }
+
+    /// Lint through a running biome server (see `biome::start_server`).
+    pub fn with_biome_server(mut self) -> Self {
+        self.biome_server = true;
+        self
+    }
+
+    /// Report findings even if `.guardrails-baseline.json` lists them.
+    pub fn without_baseline(mut self) -> Self {
+        self.baseline = None;
+        self
+    }
+
+    /// Rules for `file_path` after applying matching `overrides`.
+    fn rule_set(&self, file_path: &str) -> Arc<RuleSet> {
+        let matched = self.config.matching_overrides(file_path);
+        if matched.is_empty() {
+            return Arc::clone(&self.base);
+        }
+        let mut scoped = self.scoped.lock().unwrap_or_else(|e| e.into_inner());
+        Arc::clone(
+            scoped
+                .entry(matched)
+                .or_insert_with_key(|m| Arc::new(RuleSet::new(&self.config.with_overrides(m)))),
+        )
+    }
+
+    pub fn check(&self, file_path: &str, content: &str) -> Vec<Violation> {
+        self.check_suppressed(file_path, content).violations
+    }
+
+    /// Like `check`, but also returns the findings suppressed by directives.
+    pub fn check_suppressed(&self, file_path: &str, content: &str) -> Checked {
+        self.check_trusting(file_path, content, Trust::All)
+    }
+
+    fn check_trusting(&self, file_path: &str, content: &str, trust: Trust) -> Checked {
+        let rule_set = self.rule_set(file_path);
+        let mut violations = Vec::new();
+
+        if self.biome && rule_set.biome && is_js_ts_file(file_path) {
+            let mut found = biome::check(content, file_path, self.biome_server);
+            rule_set.biome_severity.apply(&mut found);
+            violations.extend(found);
+        }
+
+        for rule in &rule_set.rules {
+            if !rule.applies_to(file_path) {
+                continue;
+            }
+            violations.extend(rule.check(content, file_path));
+        }
+
+        let (mut violations, suppressed) =
+            suppress::apply(file_path, content, trust, violations, &self.custom_ids);
+        if let Some(baseline) = &self.baseline {
+            violations = baseline.filter(file_path, content, violations);
+        }
+        Checked {
+            violations,
+            suppressed,
+        }
+    }
+
+    /// Check `after`, dropping violations that already exist in `before`.
+    /// Only directives already in `before` suppress findings.
+    pub fn check_change(&self, file_path: &str, before: Option<&str>, after: &str) -> Checked {
+        let checked = self.check_trusting(file_path, after, Trust::ExistingIn(before));
+        match before {
+            Some(before) if !checked.violations.is_empty() => {
+                let existing = self.check(file_path, before);
+                Checked {
+                    violations: fingerprint::new_violations(
+                        &existing,
+                        before,
+                        checked.violations,
+                        after,
+                    ),
+                    suppressed: checked.suppressed,
+                }
+            }
+            _ => checked,
+        }
+    }
+}
+
+/// A project's effective config and the runner built from it.
+pub struct Project {
+    pub config: Config,
+    pub runner: Runner,
+}
+
+/// Configs and runners per project, for commands that check files from
+/// several directories. Each file gets the config of its nearest
+/// `.guardrails.json` files, like the hook and language server.
+pub struct Projects {
+    no_biome: bool,
+    baseline: bool,
+    /// Project config files for each directory seen so far.
+    dirs: Mutex<HashMap<PathBuf, Vec<PathBuf>>>,
+    /// One project per distinct set of config files.
+    projects: Mutex<HashMap<Vec<PathBuf>, Arc<Project>>>,
+}
+
+impl Projects {
+    pub fn new(no_biome: bool) -> Self {
+        Self {
+            no_biome,
+            baseline: true,
+            dirs: Mutex::new(HashMap::new()),
+            projects: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Report findings even if `.guardrails-baseline.json` lists them.
+    pub fn without_baseline(mut self) -> Self {
+        self.baseline = false;
+        self
+    }
+
+    /// Project for the file at `path`.
+    pub fn for_file(&self, path: &Path) -> Arc<Project> {
+        let dir = path.parent().unwrap_or(path);
+        let files = {
+            let mut dirs = self.dirs.lock().unwrap_or_else(|e| e.into_inner());
+            dirs.entry(dir.to_path_buf())
+                .or_insert_with(|| Config::project_files(dir))
+                .clone()
+        };
+        let mut projects = self.projects.lock().unwrap_or_else(|e| e.into_inner());
+        Arc::clone(projects.entry(files).or_insert_with(|| {
+            let mut config = Config::load_for(dir);
+            if self.no_biome {
+                config.disable_biome();
+            }
+            let mut runner = Runner::new(&config);
+            if !self.baseline {
+                runner = runner.without_baseline();
+            }
+            Arc::new(Project { config, runner })
+        }))
+    }
+
+    /// Decision for a violation under its file's project config.
+    pub fn decision(&self, violation: &Violation) -> Decision {
+        self.for_file(Path::new(&violation.file))
+            .config
+            .decision(violation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Severity;
+
+    #[test]
+    fn nested_project_config_applies_to_its_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir(root.join(".git")).unwrap();
+        std::fs::create_dir_all(root.join("packages/web/src")).unwrap();
+        std::fs::create_dir_all(root.join("packages/api/src")).unwrap();
+        std::fs::write(
+            root.join("packages/web/.guardrails.json"),
+            r#"{"rules": {"syncIo": false}, "severity": {"rules": {"security": "allow"}}}"#,
+        )
+        .unwrap();
+
+        let projects = Projects::new(true);
+        let content = "const a = fs.readFileSync(\"a\");\nel.innerHTML = x;\n";
+        let check = |path: &str| {
+            let path = root.join(path);
+            let project = projects.for_file(&path);
+            project.runner.check(&path.to_string_lossy(), content)
+        };
+        let web = check("packages/web/src/a.ts");
+        let api = check("packages/api/src/a.ts");
+        assert!(!web.iter().any(|v| v.rule == "sync-io"));
+        assert!(api.iter().any(|v| v.rule == "sync-io"));
+
+        let xss = |violations: &[Violation]| {
+            let v = violations.iter().find(|v| v.rule == "security").unwrap();
+            projects.decision(v)
+        };
+        assert_eq!(xss(&web), Decision::Allow);
+        assert_eq!(xss(&api), Decision::Deny);
+        assert!(Arc::ptr_eq(
+            &projects.for_file(&root.join("packages/web/src/a.ts")),
+            &projects.for_file(&root.join("packages/web/src/b.ts"))
+        ));
+    }
+
+    #[test]
+    fn overrides_disable_rules_per_path() {
+        let config: Config = serde_json::from_str(
+            r#"{"rules": {"biome": false}, "overrides": [{"files": ["packages/cli/**"], "rules": {"syncIo": false}}]}"#,
+        )
+        .unwrap();
+        let runner = Runner::new(&config);
+        let content = "const a = fs.readFileSync(\"a\");\n";
+
+        let rules = |path| -> Vec<String> {
+            runner
+                .check(path, content)
+                .into_iter()
+                .map(|v| v.rule)
+                .collect()
+        };
+        assert!(rules("/repo/packages/web/src/a.ts").contains(&"sync-io".to_string()));
+        assert!(!rules("/repo/packages/cli/src/a.ts").contains(&"sync-io".to_string()));
+    }
+
+    #[test]
+    fn baselined_findings_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir(root.join(".git")).unwrap();
+        std::fs::write(
+            root.join(crate::baseline::BASELINE_FILE),
+            r#"{"version": 1, "entries": [{"rule": "sync-io", "file": "src/a.ts", "hash": "HASH"}]}"#
+                .replace(
+                    "HASH",
+                    &fingerprint::line_hash(
+                        &Violation {
+                            rule: "sync-io".to_string(),
+                            severity: Severity::Medium,
+                            failure: String::new(),
+                            file: String::new(),
+                            line: Some(1),
+                            column: None,
+                            end_line: None,
+                            end_column: None,
+                        },
+                        "fs.readFileSync(a);",
+                    ),
+                ),
+        )
+        .unwrap();
+
+        let config: Config = serde_json::from_str(r#"{"rules": {"biome": false}}"#).unwrap();
+        let file = root.join("src/a.ts").to_string_lossy().into_owned();
+        let content = "// moved\nfs.readFileSync(a);\n";
+        let sync_io = |runner: &Runner| {
+            runner
+                .check(&file, content)
+                .into_iter()
+                .filter(|v| v.rule == "sync-io")
+                .count()
+        };
+        assert_eq!(sync_io(&Runner::new(&config)), 0);
+        assert_eq!(sync_io(&Runner::new(&config).without_baseline()), 1);
+    }
+
+    #[test]
+    fn configured_rule_severity_replaces_default() {
+        let config: Config = serde_json::from_str(
+            r#"{"rules": {"biome": false, "generatedFile": {"severity": "low"}}}"#,
+        )
+        .unwrap();
+        let violations = Runner::new(&config).check("/src/api.generated.ts", "x");
+        let generated = violations.iter().find(|v| v.rule == "generated-file");
+        assert_eq!(generated.map(|v| v.severity), Some(Severity::Low));
+    }
+}
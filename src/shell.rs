@@ -0,0 +1,311 @@
+//! Minimal POSIX shell command parser for Bash tool checks.
+//!
+//! Splits a command line into pipelines and simple commands, resolving quotes
+//! and escapes and collecting output redirection targets.
+//!
+//! # Limitations
+//!
+//! This is not a full shell grammar. Expansions (`$VAR`, globs, `~`) are kept
+//! verbatim, and subshells `( ... )` / `$( ... )` are flattened into separate
+//! commands. Heredoc bodies are parsed as commands.
+
+/// A single command with its arguments and the files its output is redirected to.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SimpleCommand {
+    pub words: Vec<String>,
+    pub redirects: Vec<String>,
+}
+
+impl SimpleCommand {
+    /// Arguments with leading assignments and wrappers (`sudo`, `env`, ...) removed.
+    pub fn effective_words(&self) -> &[String] {
+        let mut words = self.words.as_slice();
+        loop {
+            match words.first().map(String::as_str) {
+                Some(w) if is_assignment(w) => words = &words[1..],
+                Some("sudo") => {
+                    words = &words[1..];
+                    while let Some(flag) = words.first().filter(|w| w.starts_with('-')) {
+                        let takes_arg = matches!(flag.as_str(), "-u" | "-g" | "-C" | "-D");
+                        words = &words[if takes_arg { 2.min(words.len()) } else { 1 }..];
+                    }
+                }
+                Some("env") => {
+                    words = &words[1..];
+                    while words.first().is_some_and(|w| w.starts_with('-')) {
+                        words = &words[1..];
+                    }
+                }
+                Some("command" | "exec" | "nohup" | "time" | "nice") => words = &words[1..],
+                _ => return words,
+            }
+        }
+    }
+
+    /// Basename of the executed program, e.g. `rm` for `/bin/rm`.
+    pub fn program(&self) -> Option<&str> {
+        self.effective_words()
+            .first()
+            .map(|w| w.rsplit('/').next().unwrap_or(w))
+    }
+
+    /// Arguments after the program name.
+    pub fn args(&self) -> &[String] {
+        let words = self.effective_words();
+        if words.is_empty() {
+            words
+        } else {
+            &words[1..]
+        }
+    }
+}
+
+/// Commands connected with `|`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Pipeline {
+    pub commands: Vec<SimpleCommand>,
+}
+
+fn is_assignment(word: &str) -> bool {
+    match word.split_once('=') {
+        Some((name, _)) => {
+            !name.is_empty()
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                && !name.starts_with(|c: char| c.is_ascii_digit())
+        }
+        None => false,
+    }
+}
+
+#[derive(Default)]
+struct Parser {
+    pipelines: Vec<Pipeline>,
+    pipeline: Pipeline,
+    command: SimpleCommand,
+    word: String,
+    in_word: bool,
+    redirect_pending: bool,
+    input_pending: bool,
+}
+
+impl Parser {
+    fn end_word(&mut self) {
+        if !self.in_word {
+            return;
+        }
+        let word = std::mem::take(&mut self.word);
+        self.in_word = false;
+        if self.redirect_pending {
+            self.redirect_pending = false;
+            self.command.redirects.push(word);
+        } else if self.input_pending {
+            self.input_pending = false;
+        } else {
+            self.command.words.push(word);
+        }
+    }
+
+    fn end_command(&mut self) {
+        self.end_word();
+        self.redirect_pending = false;
+        self.input_pending = false;
+        let command = std::mem::take(&mut self.command);
+        if !command.words.is_empty() || !command.redirects.is_empty() {
+            self.pipeline.commands.push(command);
+        }
+    }
+
+    fn end_pipeline(&mut self) {
+        self.end_command();
+        let pipeline = std::mem::take(&mut self.pipeline);
+        if !pipeline.commands.is_empty() {
+            self.pipelines.push(pipeline);
+        }
+    }
+
+    fn push(&mut self, c: char) {
+        self.word.push(c);
+        self.in_word = true;
+    }
+
+    /// Drop a file descriptor prefix like the `2` in `2>file`.
+    fn drop_fd_prefix(&mut self) {
+        if self.in_word && self.word.chars().all(|c| c.is_ascii_digit()) {
+            self.word.clear();
+            self.in_word = false;
+        }
+    }
+}
+
+pub fn parse(command: &str) -> Vec<Pipeline> {
+    let chars: Vec<char> = command.chars().collect();
+    let mut p = Parser::default();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        match c {
+            '\'' => {
+                p.in_word = true;
+                i += 1;
+                while i < chars.len() && chars[i] != '\'' {
+                    p.word.push(chars[i]);
+                    i += 1;
+                }
+            }
+            '"' => {
+                p.in_word = true;
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' && i + 1 < chars.len() {
+                        i += 1;
+                    }
+                    p.word.push(chars[i]);
+                    i += 1;
+                }
+            }
+            '\\' => {
+                if let Some(n) = next {
+                    if n != '\n' {
+                        p.push(n);
+                    }
+                    i += 1;
+                }
+            }
+            '#' if !p.in_word => {
+                while i + 1 < chars.len() && chars[i + 1] != '\n' {
+                    i += 1;
+                }
+            }
+            ' ' | '\t' => p.end_word(),
+            '\n' | ';' | '(' | ')' => p.end_pipeline(),
+            '|' if next == Some('|') => {
+                p.end_pipeline();
+                i += 1;
+            }
+            '|' => {
+                p.end_command();
+                if next == Some('&') {
+                    i += 1;
+                }
+            }
+            '&' if next == Some('&') => {
+                p.end_pipeline();
+                i += 1;
+            }
+            '&' if next == Some('>') => {
+                p.end_word();
+                i += 1;
+                if chars.get(i + 1) == Some(&'>') {
+                    i += 1;
+                }
+                p.redirect_pending = true;
+            }
+            '&' => p.end_pipeline(),
+            '>' => {
+                p.drop_fd_prefix();
+                p.end_word();
+                match next {
+                    Some('>') | Some('|') => i += 1,
+                    Some('&') => {
+                        // `>&2` duplicates a descriptor; `>&file` redirects to a file.
+                        i += 1;
+                        if chars
+                            .get(i + 1)
+                            .is_some_and(|c| c.is_ascii_digit() || *c == '-')
+                        {
+                            i += 2;
+                            continue;
+                        }
+                    }
+                    _ => {}
+                }
+                p.redirect_pending = true;
+            }
+            '<' => {
+                p.drop_fd_prefix();
+                p.end_word();
+                while chars.get(i + 1) == Some(&'<') {
+                    i += 1;
+                }
+                p.input_pending = true;
+            }
+            _ => p.push(c),
+        }
+        i += 1;
+    }
+
+    p.end_pipeline();
+    p.pipelines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(cmd: &SimpleCommand) -> Vec<&str> {
+        cmd.words.iter().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn splits_pipelines_and_commands() {
+        let pipelines = parse("cd /tmp && curl -s x | sh; echo done");
+        assert_eq!(pipelines.len(), 3);
+        assert_eq!(pipelines[1].commands.len(), 2);
+        assert_eq!(words(&pipelines[1].commands[1]), ["sh"]);
+    }
+
+    #[test]
+    fn resolves_quotes_and_escapes() {
+        let pipelines = parse(r#"echo 'a b' "c \"d\"" e\ f"#);
+        assert_eq!(
+            words(&pipelines[0].commands[0]),
+            ["echo", "a b", "c \"d\"", "e f"]
+        );
+    }
+
+    #[test]
+    fn quoted_operators_are_literal() {
+        let pipelines = parse("echo 'a | b > c'");
+        assert_eq!(pipelines.len(), 1);
+        assert!(pipelines[0].commands[0].redirects.is_empty());
+    }
+
+    #[test]
+    fn collects_redirect_targets() {
+        let pipelines = parse("echo X > .env; cat a >> out.txt 2>/dev/null; cmd &> log");
+        assert_eq!(pipelines[0].commands[0].redirects, [".env"]);
+        assert_eq!(pipelines[1].commands[0].redirects, ["out.txt", "/dev/null"]);
+        assert_eq!(words(&pipelines[1].commands[0]), ["cat", "a"]);
+        assert_eq!(pipelines[2].commands[0].redirects, ["log"]);
+    }
+
+    #[test]
+    fn descriptor_duplication_is_not_a_target() {
+        let pipelines = parse("cmd 2>&1 >out");
+        assert_eq!(pipelines[0].commands[0].redirects, ["out"]);
+        assert_eq!(words(&pipelines[0].commands[0]), ["cmd"]);
+    }
+
+    #[test]
+    fn input_redirect_is_skipped() {
+        let pipelines = parse("sort < in.txt");
+        assert_eq!(words(&pipelines[0].commands[0]), ["sort"]);
+    }
+
+    #[test]
+    fn strips_wrappers() {
+        let pipelines = parse("sudo -u root env FOO=1 /bin/rm -rf /");
+        let cmd = &pipelines[0].commands[0];
+        assert_eq!(cmd.program(), Some("rm"));
+        assert_eq!(cmd.args(), ["-rf", "/"]);
+    }
+
+    #[test]
+    fn ignores_comments() {
+        let pipelines = parse("ls # rm -rf /");
+        assert_eq!(words(&pipelines[0].commands[0]), ["ls"]);
+    }
+}
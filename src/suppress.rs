@@ -0,0 +1,473 @@
+//! Inline suppression directives.
+//!
+//! `// guardrails-ignore-next-line syncIo -- reason` hides findings on the next
+//! line, `/* guardrails-ignore-file bundleSize -- reason */` hides them in the
+//! whole file. Rules are config keys (`syncIo`) or rule ids (`sync-io`) and
+//! at least one is required. Comments come from the lexer, so directive text
+//! inside strings, regexes and JSX text is ignored. A directive without a
+//! reason or rules suppresses nothing and is reported itself. Findings about
+//! the path rather than a line can't be suppressed, and a directive added by
+//! the edit being checked doesn't apply to that edit. A directive counts as
+//! already there when an identical one was in the file before and, for
+//! next-line directives, the line it covers is unchanged.
+
+use crate::lexer::{build_line_offsets, is_jsx_file, offset_to_line, tokenize, TokenKind};
+use crate::rules::{is_known_rule, rule_matches, Severity, Violation};
+
+const NEXT_LINE: &str = "guardrails-ignore-next-line";
+const FILE: &str = "guardrails-ignore-file";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    NextLine,
+    File,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Directive {
+    scope: Scope,
+    rules: Vec<String>,
+    reason: Option<String>,
+    /// Line the comment ends on.
+    line: u32,
+}
+
+impl Directive {
+    fn covers(&self, v: &Violation) -> bool {
+        let in_scope = match self.scope {
+            Scope::File => v.line.is_some(),
+            Scope::NextLine => v.line == Some(self.line + 1),
+        };
+        in_scope && self.rules.iter().any(|r| rule_matches(r, &v.rule))
+    }
+
+    /// Same directive, wherever it sits in the file.
+    fn same_as(&self, other: &Directive) -> bool {
+        self.scope == other.scope && self.rules == other.rules && self.reason == other.reason
+    }
+
+    /// Text of the line a next-line directive covers, trimmed so reindenting keeps it.
+    fn covered_line<'a>(&self, lines: &[&'a str]) -> Option<&'a str> {
+        match self.scope {
+            Scope::File => None,
+            Scope::NextLine => lines.get(self.line as usize).map(|l| l.trim()),
+        }
+    }
+}
+
+/// Which directives in a file may suppress findings.
+#[derive(Debug, Clone, Copy)]
+pub enum Trust<'a> {
+    /// Every directive, e.g. when checking a file on disk.
+    All,
+    /// Only directives already in this earlier version of the file, so an
+    /// edit can't suppress its own findings. None for a new file.
+    ExistingIn(Option<&'a str>),
+}
+
+/// A finding hidden by a directive, kept so it can be listed in the output.
+#[derive(Debug, Clone)]
+pub struct Suppression {
+    pub violation: Violation,
+    pub reason: String,
+}
+
+/// Parse the text of one comment, without its `//` or `/* */` delimiters.
+fn parse_directive(text: &str, line: u32) -> Option<Directive> {
+    let text = text.trim().trim_start_matches('*').trim_start();
+    let (scope, rest) = if let Some(rest) = text.strip_prefix(NEXT_LINE) {
+        (Scope::NextLine, rest)
+    } else if let Some(rest) = text.strip_prefix(FILE) {
+        (Scope::File, rest)
+    } else {
+        return None;
+    };
+    if rest.starts_with(|c: char| !c.is_whitespace()) {
+        return None;
+    }
+
+    let (names, reason) = match rest.split_once("--") {
+        Some((names, reason)) => (names, Some(reason.trim()).filter(|r| !r.is_empty())),
+        None => (rest, None),
+    };
+    Some(Directive {
+        scope,
+        rules: names
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|n| !n.is_empty())
+            .map(String::from)
+            .collect(),
+        reason: reason.map(String::from),
+        line,
+    })
+}
+
+fn parse_directives(content: &str, jsx: bool) -> Vec<Directive> {
+    let offsets = build_line_offsets(content);
+    tokenize(content, jsx)
+        .iter()
+        .filter_map(|token| {
+            let text = match token.kind {
+                TokenKind::LineComment => token.text(content).strip_prefix("//")?,
+                TokenKind::BlockComment => {
+                    let text = &token.text(content)[2..];
+                    text.strip_suffix("*/").unwrap_or(text)
+                }
+                _ => return None,
+            };
+            let line = offset_to_line(&offsets, token.end.saturating_sub(1).max(token.start));
+            parse_directive(text, line as u32)
+        })
+        .collect()
+}
+
+/// Rule id for malformed directives and unknown rule names.
+pub const INVALID_SUPPRESSION: &str = "invalid-suppression";
+
+fn problem(file_path: &str, line: u32, failure: String) -> Violation {
+    Violation {
+        rule: INVALID_SUPPRESSION.to_string(),
+        severity: Severity::Medium,
+        failure,
+        file: file_path.to_string(),
+        line: Some(line),
+        column: None,
+        end_line: None,
+        end_column: None,
+    }
+}
+
+/// Which of `directives` in `after` were already in `before`. Each earlier
+/// directive vouches for at most one later one, and a next-line directive
+/// only while the line it covers is unchanged.
+fn trusted_directives(directives: &[Directive], after: &str, before: &str, jsx: bool) -> Vec<bool> {
+    let after_lines: Vec<&str> = after.lines().collect();
+    let before_lines: Vec<&str> = before.lines().collect();
+    let mut existing: Vec<Option<Directive>> = parse_directives(before, jsx)
+        .into_iter()
+        .map(Some)
+        .collect();
+
+    directives
+        .iter()
+        .map(|d| {
+            let covered = d.covered_line(&after_lines);
+            let matching = existing.iter_mut().find(|e| {
+                e.as_ref()
+                    .is_some_and(|e| e.same_as(d) && e.covered_line(&before_lines) == covered)
+            });
+            matching.map(Option::take).is_some()
+        })
+        .collect()
+}
+
+/// Split `violations` into kept and suppressed ones. Directives without a
+/// reason or naming unknown rules, and untrusted directives that would have
+/// hidden a finding, are added to the kept list as findings.
+/// `custom_rules` are the ids from `customRules`, which are also valid names.
+pub fn apply(
+    file_path: &str,
+    content: &str,
+    trust: Trust,
+    violations: Vec<Violation>,
+    custom_rules: &[String],
+) -> (Vec<Violation>, Vec<Suppression>) {
+    if !content.contains("guardrails-ignore-") {
+        return (violations, Vec::new());
+    }
+
+    let jsx = is_jsx_file(file_path);
+    let directives = parse_directives(content, jsx);
+    let trusted = match trust {
+        Trust::All => vec![true; directives.len()],
+        Trust::ExistingIn(before) => {
+            trusted_directives(&directives, content, before.unwrap_or(""), jsx)
+        }
+    };
+    let mut kept = Vec::new();
+    let mut suppressed = Vec::new();
+    let mut untrusted_used = vec![false; directives.len()];
+
+    for v in violations {
+        let mut covering = directives
+            .iter()
+            .enumerate()
+            .filter(|(_, d)| d.reason.is_some() && d.covers(&v));
+        match covering.clone().find(|(i, _)| trusted[*i]) {
+            Some((_, d)) => suppressed.push(Suppression {
+                violation: v,
+                reason: d.reason.clone().unwrap_or_default(),
+            }),
+            None => {
+                if let Some((i, _)) = covering.next() {
+                    untrusted_used[i] = true;
+                }
+                kept.push(v);
+            }
+        }
+    }
+
+    for (i, d) in directives.iter().enumerate() {
+        let name = match d.scope {
+            Scope::NextLine => NEXT_LINE,
+            Scope::File => FILE,
+        };
+        if untrusted_used[i] {
+            kept.push(problem(
+                file_path,
+                d.line,
+                format!(
+                    "Suppression was added by this edit and does not apply to its own findings. Fix the code, or ask the user to add the {} directive.",
+                    name
+                ),
+            ));
+        }
+        if d.reason.is_none() {
+            let directive = format!("{} {}", name, d.rules.join(","));
+            kept.push(problem(
+                file_path,
+                d.line,
+                format!(
+                    "Suppression has no reason and is ignored. Explain why: {} -- <reason>",
+                    directive.trim_end()
+                ),
+            ));
+        }
+        if d.rules.is_empty() {
+            kept.push(problem(
+                file_path,
+                d.line,
+                format!(
+                    "Suppression names no rules and is ignored. List the rules it covers: {} <rule>, ... -- <reason>",
+                    name
+                ),
+            ));
+        }
+        let unknown = d
+            .rules
+            .iter()
+            .filter(|r| !is_known_rule(r) && !custom_rules.contains(r));
+        for unknown in unknown {
+            kept.push(problem(
+                file_path,
+                d.line,
+                format!("Unknown rule {:?} in {} directive", unknown, name),
+            ));
+        }
+    }
+
+    (kept, suppressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn violation(rule: &str, line: u32) -> Violation {
+        Violation {
+            rule: rule.to_string(),
+            severity: Severity::High,
+            failure: String::new(),
+            file: "/src/a.ts".to_string(),
+            line: Some(line),
+            column: None,
+            end_line: None,
+            end_column: None,
+        }
+    }
+
+    fn rules(violations: &[Violation]) -> Vec<&str> {
+        violations.iter().map(|v| v.rule.as_str()).collect()
+    }
+
+    #[test]
+    fn next_line_suppresses_only_the_next_line() {
+        let content = "// guardrails-ignore-next-line syncIo -- startup only\nreadFileSync(a);\nreadFileSync(b);\n";
+        let (kept, suppressed) = apply(
+            "/src/a.ts",
+            content,
+            Trust::All,
+            vec![violation("sync-io", 2), violation("sync-io", 3)],
+            &[],
+        );
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].line, Some(3));
+        assert_eq!(suppressed[0].reason, "startup only");
+    }
+
+    #[test]
+    fn file_directive_in_block_comment() {
+        let content = "/* guardrails-ignore-file bundleSize, dom-access -- vendored */\nimport _ from 'lodash';\n";
+        let (kept, suppressed) = apply(
+            "/src/a.ts",
+            content,
+            Trust::All,
+            vec![
+                violation("bundle-size", 2),
+                violation("dom-access", 9),
+                violation("sync-io", 2),
+            ],
+            &[],
+        );
+        assert_eq!(rules(&kept), ["sync-io"]);
+        assert_eq!(suppressed.len(), 2);
+    }
+
+    #[test]
+    fn missing_reason_is_reported_and_suppresses_nothing() {
+        let content = "// guardrails-ignore-next-line syncIo\nreadFileSync(a);\n";
+        let (kept, suppressed) = apply(
+            "/src/a.ts",
+            content,
+            Trust::All,
+            vec![violation("sync-io", 2)],
+            &[],
+        );
+        assert!(suppressed.is_empty());
+        assert_eq!(rules(&kept), ["sync-io", "invalid-suppression"]);
+    }
+
+    #[test]
+    fn unknown_rule_is_reported() {
+        let content = "// guardrails-ignore-next-line syncIO -- typo\nx();\n";
+        let (kept, _) = apply("/src/a.ts", content, Trust::All, Vec::new(), &[]);
+        assert!(kept[0].failure.contains("syncIO"));
+
+        let content = "// guardrails-ignore-next-line no-axios -- legacy client
+x();
+";
+        let (kept, _) = apply(
+            "/src/a.ts",
+            content,
+            Trust::All,
+            Vec::new(),
+            &["no-axios".to_string()],
+        );
+        assert!(kept.is_empty());
+    }
+
+    #[test]
+    fn directive_in_string_is_ignored() {
+        let content =
+            "const s = \"// guardrails-ignore-next-line syncIo -- no\";\nreadFileSync(a);\n";
+        let (kept, suppressed) = apply(
+            "/src/a.ts",
+            content,
+            Trust::All,
+            vec![violation("sync-io", 2)],
+            &[],
+        );
+        assert!(suppressed.is_empty());
+        assert_eq!(kept.len(), 1);
+    }
+
+    #[test]
+    fn directive_without_rules_suppresses_nothing() {
+        let content = "/* guardrails-ignore-file -- ok */\nreadFileSync(a);\n";
+        let (kept, suppressed) = apply(
+            "/src/a.ts",
+            content,
+            Trust::All,
+            vec![violation("sync-io", 2)],
+            &[],
+        );
+        assert!(suppressed.is_empty());
+        assert_eq!(rules(&kept), ["sync-io", "invalid-suppression"]);
+    }
+
+    #[test]
+    fn path_findings_are_never_suppressed() {
+        let content = "/* guardrails-ignore-file sensitive-file -- fixture */\n";
+        let mut path_finding = violation("sensitive-file", 1);
+        path_finding.line = None;
+        let (kept, suppressed) = apply("/src/a.ts", content, Trust::All, vec![path_finding], &[]);
+        assert!(suppressed.is_empty());
+        assert_eq!(rules(&kept), ["sensitive-file"]);
+    }
+
+    #[test]
+    fn directives_added_by_the_edit_are_not_trusted() {
+        let directive = "// guardrails-ignore-next-line syncIo -- startup only\n";
+        let after = format!("{}readFileSync(a);\n", directive);
+        let findings = || vec![violation("sync-io", 2)];
+
+        let (kept, _) = apply(
+            "/src/a.ts",
+            &after,
+            Trust::ExistingIn(None),
+            findings(),
+            &[],
+        );
+        assert_eq!(rules(&kept), ["sync-io", "invalid-suppression"]);
+        let before = "readFileSync(b);\n";
+        let (kept, _) = apply(
+            "/src/a.ts",
+            &after,
+            Trust::ExistingIn(Some(before)),
+            findings(),
+            &[],
+        );
+        assert_eq!(rules(&kept), ["sync-io", "invalid-suppression"]);
+        assert!(kept[1].failure.contains("added by this edit"));
+
+        // Moved by the edit together with the line it covers.
+        let before = format!("x();\n\n  {}  readFileSync(a);\n", directive);
+        let (kept, suppressed) = apply(
+            "/src/a.ts",
+            &after,
+            Trust::ExistingIn(Some(&before)),
+            findings(),
+            &[],
+        );
+        assert!(kept.is_empty());
+        assert_eq!(suppressed.len(), 1);
+
+        // Same directive, but the line below it changed.
+        let before = format!("{}x();\n", directive);
+        let (kept, _) = apply(
+            "/src/a.ts",
+            &after,
+            Trust::ExistingIn(Some(&before)),
+            findings(),
+            &[],
+        );
+        assert_eq!(rules(&kept), ["sync-io", "invalid-suppression"]);
+    }
+
+    #[test]
+    fn each_existing_directive_vouches_for_one_copy() {
+        let directive = "// guardrails-ignore-next-line security -- sanitized upstream\n";
+        let before = format!("{}el.innerHTML = html;\n", directive);
+        let after = format!(
+            "{}el.innerHTML = html;\n{}other.innerHTML = userInput;\n",
+            directive, directive
+        );
+        let finding = |line| violation("security", line);
+        let (kept, suppressed) = apply(
+            "/src/a.ts",
+            &after,
+            Trust::ExistingIn(Some(&before)),
+            vec![finding(2), finding(4)],
+            &[],
+        );
+        assert_eq!(suppressed.len(), 1);
+        assert_eq!(suppressed[0].violation.line, Some(2));
+        assert_eq!(kept[0].line, Some(4));
+        assert_eq!(kept[1].rule, INVALID_SUPPRESSION);
+        assert_eq!(kept[1].line, Some(3));
+    }
+
+    #[test]
+    fn biome_key_covers_biome_findings() {
+        let content = "// guardrails-ignore-next-line biome -- generated\nconsole.log(x);\n";
+        let (kept, suppressed) = apply(
+            "/src/a.ts",
+            content,
+            Trust::All,
+            vec![violation("biome/lint/suspicious/noConsole", 2)],
+            &[],
+        );
+        assert!(kept.is_empty());
+        assert_eq!(suppressed.len(), 1);
+    }
+}